@@ -49,7 +49,12 @@
             openssl
           ];
           src = ./.;
-          cargoHash = "sha256-c9tGHhJljYFUDxKT3YXwNnvree2CuKMSUgHzKDi838o=";
+          # read from the tracked lockfile, so there's no vendor hash to go stale when it changes
+          cargoLock = {
+            lockFile = ./Cargo.lock;
+            # zscript_parser is a git dependency, already pinned to a commit by Cargo.lock
+            allowBuiltinFetchGit = true;
+          };
         };
       in
       {
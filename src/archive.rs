@@ -0,0 +1,125 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use sha2::{Digest, Sha256};
+
+/// Where an archive given on the command line or as a dependency is read from.
+pub struct ArchivePaths {
+    /// The folder holding the archive's files.
+    pub folder: PathBuf,
+    /// The folder that relative paths in the archive's `zscdoc.toml` are resolved from.
+    pub relative_to: PathBuf,
+}
+
+fn extension(path: &Path) -> Option<String> {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+}
+
+/// Names of the zip compression methods that can't be extracted, since `zip` only reports them
+/// as unsupported.
+const UNSUPPORTED_ZIP_METHODS: &[(zip::CompressionMethod, &str)] = &[
+    (zip::CompressionMethod::SHRINK, "Shrink"),
+    (zip::CompressionMethod::IMPLODE, "Implode"),
+    (zip::CompressionMethod::DEFLATE64, "Deflate64"),
+    (zip::CompressionMethod::LZMA, "LZMA"),
+    (zip::CompressionMethod::XZ, "XZ"),
+    (zip::CompressionMethod::PPMD, "PPMd"),
+];
+
+/// Fails with the name of the method if a file in the archive is compressed in a way that can't
+/// be extracted, rather than leaving `zip` to report it without saying which.
+fn check_zip_compression(archive: &mut zip::ZipArchive<std::fs::File>) -> anyhow::Result<()> {
+    use zip::CompressionMethod;
+    for i in 0..archive.len() {
+        let file = archive
+            .by_index_raw(i)
+            .context("failed to read zip archive")?;
+        let method = file.compression();
+        if [
+            CompressionMethod::STORE,
+            CompressionMethod::DEFLATE,
+            CompressionMethod::BZIP2,
+            CompressionMethod::ZSTD,
+        ]
+        .contains(&method)
+        {
+            continue;
+        }
+        let name = UNSUPPORTED_ZIP_METHODS
+            .iter()
+            .find(|(m, _)| *m == method)
+            .map(|(_, n)| n.to_string())
+            .unwrap_or_else(|| format!("{:?}", method));
+        anyhow::bail!(
+            "{:?} is compressed with {}, which can't be extracted; repack the archive using \
+            Deflate, BZip2 or Zstandard",
+            file.name(),
+            name
+        );
+    }
+    Ok(())
+}
+
+fn extract(path: &Path, destination: &Path) -> anyhow::Result<()> {
+    match extension(path).as_deref() {
+        Some("pk3" | "ipk3" | "zip") => {
+            let file = std::fs::File::open(path).context("failed to open archive")?;
+            let mut archive = zip::ZipArchive::new(file).context("failed to read zip archive")?;
+            check_zip_compression(&mut archive)?;
+            archive
+                .extract(destination)
+                .context("failed to extract zip archive")?;
+        }
+        Some("pk7" | "ipk7" | "7z") => {
+            sevenz_rust::decompress_file(path, destination)
+                .context("failed to extract 7z archive")?;
+        }
+        _ => anyhow::bail!(
+            "{:?} isn't a folder or a .pk3, .ipk3, .zip, .pk7, .ipk7 or .7z file",
+            path
+        ),
+    }
+    Ok(())
+}
+
+/// Finds the folder to read an archive from. Folders are used as they are, while packed archives
+/// are extracted into the cache, keyed by a hash of their contents so that they're only extracted
+/// again when they change.
+pub fn unpack(path: &Path) -> anyhow::Result<ArchivePaths> {
+    if path.is_dir() {
+        return Ok(ArchivePaths {
+            folder: path.to_path_buf(),
+            relative_to: path.to_path_buf(),
+        });
+    }
+
+    let bytes = std::fs::read(path).context(format!("couldn't read archive {:?}", path))?;
+    let hash = Sha256::digest(&bytes);
+    let unpacked_dir = crate::cache::cache_dir()?.join("archives");
+    let folder = unpacked_dir.join(format!("{:x}", hash));
+    if !folder.exists() {
+        eprintln!("Extracting {:?}...", path);
+        // extracting somewhere else first means an interrupted extraction is never mistaken for
+        // a finished one
+        let partial = unpacked_dir.join(format!("{:x}.partial", hash));
+        if partial.exists() {
+            std::fs::remove_dir_all(&partial)?;
+        }
+        std::fs::create_dir_all(&partial).context("failed to make extraction directory")?;
+        extract(path, &partial).context(format!("extracting {:?}", path))?;
+        std::fs::rename(&partial, &folder).context("failed to move extracted archive")?;
+        // lets `zscdoc cache list` say where the folder came from
+        let source = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        std::fs::write(
+            unpacked_dir.join(format!("{:x}.source", hash)),
+            source.to_string_lossy().as_bytes(),
+        )
+        .context("failed to record archive source")?;
+    }
+    Ok(ArchivePaths {
+        folder,
+        relative_to: path.parent().map(|p| p.to_path_buf()).unwrap_or_default(),
+    })
+}
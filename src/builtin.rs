@@ -1,7 +1,7 @@
 use itertools::Itertools;
 use zscript_parser::{
     ast,
-    err::{ParsingError, ParsingErrorLevel},
+    err::{ParsingError, ParsingErrorLevel, ToDisplayedErrors},
     filesystem::{File, FileIndex, Files},
     hir::{self, lower::HirLowerer},
     interner::NameSymbol,
@@ -9,7 +9,7 @@ use zscript_parser::{
     parser::Parser,
 };
 
-use crate::{item::ItemProvider, option_slice_to_slice, structures::Owner};
+use crate::{item::ItemProvider, option_slice_to_slice, option_vec_to_vec, structures::Owner};
 
 #[derive(serde::Deserialize, Debug)]
 pub struct MemberVariableFromFile {
@@ -26,11 +26,31 @@ pub struct ConstantFromFile {
     def: String,
 }
 
+#[derive(serde::Deserialize, Debug)]
+pub struct EnumFromFile {
+    def: String,
+}
+
+/// An operator of a builtin type, such as `Vector2 + Vector2`. ZScript has no syntax for
+/// declaring these, so the types are just names that get resolved to links where possible.
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct OperatorFromFile {
+    symbol: String,
+    /// The left operand, which unary operators don't have.
+    lhs: Option<String>,
+    rhs: String,
+    returns: String,
+    #[serde(default)]
+    doc: String,
+}
+
 #[derive(serde::Deserialize, Debug)]
 pub struct BuiltinTypeFromFile {
     #[serde(skip)]
     pub(crate) filename: String,
     name: String,
+    /// The names of the type parameters of generic containers, such as `["K", "V"]` for `Map`.
+    type_params: Option<Vec<String>>,
     doc: String,
     uses_things_from: Option<String>,
     #[serde(alias = "member")]
@@ -39,6 +59,13 @@ pub struct BuiltinTypeFromFile {
     functions: Option<Vec<FunctionFromFile>>,
     #[serde(alias = "constant")]
     constants: Option<Vec<ConstantFromFile>>,
+    #[serde(alias = "enum")]
+    enums: Option<Vec<EnumFromFile>>,
+    #[serde(alias = "operator")]
+    operators: Option<Vec<OperatorFromFile>>,
+    /// Structs nested inside the builtin, which are described the same way as builtins.
+    #[serde(alias = "struct")]
+    structs: Option<Vec<BuiltinTypeFromFile>>,
 }
 
 #[derive(Debug)]
@@ -56,15 +83,24 @@ pub struct ConstantHir {
     pub def: ir_common::ConstDefinition,
 }
 
+#[derive(Debug)]
+pub struct EnumHir {
+    pub def: ir_common::EnumDefinition,
+}
+
 #[derive(Debug)]
 pub struct BuiltinTypeHir {
     pub filename: String,
     pub name: String,
+    pub type_params: Vec<String>,
     pub doc: String,
     pub uses_things_from: Option<String>,
     pub members: Vec<MemberVariableHir>,
     pub functions: Vec<FunctionHir>,
     pub constants: Vec<ConstantHir>,
+    pub enums: Vec<EnumHir>,
+    pub operators: Vec<OperatorFromFile>,
+    pub structs: Vec<BuiltinTypeHir>,
 }
 
 impl BuiltinTypeFromFile {
@@ -73,6 +109,7 @@ impl BuiltinTypeFromFile {
             doc: self.doc,
             filename: self.filename.clone(),
             name: self.name,
+            type_params: option_vec_to_vec(self.type_params),
             uses_things_from: self.uses_things_from,
             members: option_slice_to_slice(self.members.as_deref())
                 .iter()
@@ -86,6 +123,450 @@ impl BuiltinTypeFromFile {
                 .iter()
                 .map(|m| m.produce(files, &self.filename))
                 .collect::<Result<Vec<_>, _>>()?,
+            enums: option_slice_to_slice(self.enums.as_deref())
+                .iter()
+                .map(|m| m.produce(files, &self.filename))
+                .collect::<Result<Vec<_>, _>>()?,
+            operators: option_vec_to_vec(self.operators),
+            structs: option_vec_to_vec(self.structs)
+                .into_iter()
+                .map(|mut s| {
+                    s.filename = self.filename.clone();
+                    s.produce(files)
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+        })
+    }
+}
+
+/// A builtin file from `[archive] builtins`, which is either TOML describing a single builtin, or
+/// a ZScript-like declaration file describing any number of them.
+#[derive(Debug)]
+pub enum BuiltinSource {
+    Toml(BuiltinTypeFromFile),
+    Declarations(BuiltinDeclarationFile),
+}
+
+impl BuiltinSource {
+    pub fn produce(self, files: &mut Files) -> anyhow::Result<Vec<BuiltinTypeHir>> {
+        match self {
+            Self::Toml(b) => b
+                .produce(files)
+                .map(|b| vec![b])
+                .map_err(|e| anyhow::anyhow!(e.to_displayed_errors(files))),
+            Self::Declarations(d) => d.produce(files),
+        }
+    }
+}
+
+/// A file of declarations like
+///
+/// ```text
+/// /// A builtin type representing 2D coordinates.
+/// builtin Vector2 {
+///     /// The x coordinate.
+///     double x;
+/// }
+/// ```
+///
+/// where `builtin Name uses SomeStruct { ... }` does the same as `uses_things_from`. Builtins
+/// can contain enums, structs written like builtins with `struct` instead of `builtin`, and
+/// operators written like `operator Vector2 + Vector2 -> Vector2;`.
+#[derive(Debug)]
+pub struct BuiltinDeclarationFile {
+    pub(crate) filename: String,
+    pub(crate) text: String,
+}
+
+/// A `builtin` block found in a declaration file, before its body is parsed.
+struct BuiltinDeclaration {
+    name: String,
+    type_params: Vec<String>,
+    uses_things_from: Option<String>,
+    doc: String,
+    /// The byte range of everything between the braces.
+    body: std::ops::Range<usize>,
+    structs: Vec<BuiltinDeclaration>,
+    operators: Vec<OperatorFromFile>,
+    /// The byte ranges of nested structs and operators in the body, which the parser mustn't see.
+    nested: Vec<std::ops::Range<usize>>,
+}
+
+/// Finds the `builtin` blocks of a declaration file. This only needs to understand enough of
+/// ZScript to find the end of each block - the bodies are left to the real parser.
+struct DeclarationScanner<'a> {
+    filename: &'a str,
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> DeclarationScanner<'a> {
+    fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    fn error(&self, msg: &str) -> anyhow::Error {
+        let line = self.text[..self.pos].matches('\n').count() + 1;
+        anyhow::anyhow!("{}:{}: {}", self.filename, line, msg)
+    }
+
+    /// Skips whitespace and comments, returning the text of any `///` doc comment lines.
+    fn skip_trivia(&mut self) -> anyhow::Result<Vec<&'a str>> {
+        let mut doc_lines = vec![];
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+            if let Some(comment) = trimmed.strip_prefix("//") {
+                let line = comment.split('\n').next().unwrap_or_default();
+                self.pos += 2 + line.len();
+                match line.strip_prefix('/') {
+                    Some(doc) => doc_lines.push(doc.strip_prefix(' ').unwrap_or(doc).trim_end()),
+                    None => doc_lines.clear(),
+                }
+            } else if trimmed.starts_with("/*") {
+                let end = trimmed[2..]
+                    .find("*/")
+                    .ok_or_else(|| self.error("unterminated block comment"))?;
+                self.pos += end + 4;
+                doc_lines.clear();
+            } else {
+                return Ok(doc_lines);
+            }
+        }
+    }
+
+    fn identifier(&mut self) -> anyhow::Result<&'a str> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.error("expected an identifier"));
+        }
+        self.pos += len;
+        Ok(&rest[..len])
+    }
+
+    /// Skips the rest of a string or name literal whose opening quote has just been passed.
+    fn skip_string(&mut self, quote: char) -> anyhow::Result<()> {
+        let mut chars = self.rest().chars();
+        loop {
+            let next = chars
+                .next()
+                .ok_or_else(|| self.error("unterminated string"))?;
+            self.pos += next.len_utf8();
+            if next == '\\' {
+                if let Some(escaped) = chars.next() {
+                    self.pos += escaped.len_utf8();
+                }
+            } else if next == quote {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Skips to just after the `}` matching a `{` that's just been passed.
+    fn skip_block(&mut self) -> anyhow::Result<()> {
+        let mut depth = 1;
+        while depth > 0 {
+            self.skip_trivia()?;
+            let c = self
+                .rest()
+                .chars()
+                .next()
+                .ok_or_else(|| self.error("expected `}` before the end of the file"))?;
+            self.pos += c.len_utf8();
+            match c {
+                '{' => depth += 1,
+                '}' => depth -= 1,
+                '"' | '\'' => self.skip_string(c)?,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Skips a declaration inside a block, stopping before the `}` that closes the block.
+    fn skip_statement(&mut self) -> anyhow::Result<()> {
+        loop {
+            self.skip_trivia()?;
+            let c = self
+                .rest()
+                .chars()
+                .next()
+                .ok_or_else(|| self.error("expected `}` before the end of the file"))?;
+            if c == '}' {
+                return Ok(());
+            }
+            self.pos += c.len_utf8();
+            match c {
+                ';' => return Ok(()),
+                // function bodies and enums end at their closing brace, with an optional `;`
+                '{' => {
+                    self.skip_block()?;
+                    self.skip_trivia()?;
+                    if self.rest().starts_with(';') {
+                        self.pos += 1;
+                    }
+                    return Ok(());
+                }
+                '"' | '\'' => self.skip_string(c)?,
+                _ => {}
+            }
+        }
+    }
+
+    /// Reads the rest of an operator whose keyword has just been passed, which is written like
+    /// `operator Vector2 + Vector2 -> Vector2;`, or `operator - Vector2 -> Vector2;` if unary.
+    fn operator(&mut self, doc: String) -> anyhow::Result<OperatorFromFile> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| c == ';' || c == '{' || c == '}')
+            .filter(|i| rest[*i..].starts_with(';'))
+            .ok_or_else(|| self.error("expected `;` after operator"))?;
+        let parts = rest[..len].split_whitespace().collect_vec();
+        let (operands, returns) = match parts.as_slice() {
+            [operands @ .., "->", returns] => (operands, returns.to_string()),
+            _ => return Err(self.error("expected `-> ReturnType` at the end of operator")),
+        };
+        let (lhs, symbol, rhs) = match operands {
+            [symbol, rhs] => (None, symbol, rhs),
+            [lhs, symbol, rhs] => (Some(lhs.to_string()), symbol, rhs),
+            _ => return Err(self.error("expected `Type symbol Type` or `symbol Type` in operator")),
+        };
+        self.pos += len + 1;
+        Ok(OperatorFromFile {
+            symbol: symbol.to_string(),
+            lhs,
+            rhs: rhs.to_string(),
+            returns,
+            doc,
+        })
+    }
+
+    /// Reads the rest of a `builtin` or `struct` block whose keyword has just been passed.
+    fn block(&mut self, doc: String) -> anyhow::Result<BuiltinDeclaration> {
+        self.skip_trivia()?;
+        let name = self.identifier()?.to_string();
+        self.skip_trivia()?;
+        let mut type_params = vec![];
+        if self.rest().starts_with('<') {
+            self.pos += 1;
+            loop {
+                self.skip_trivia()?;
+                type_params.push(self.identifier()?.to_string());
+                self.skip_trivia()?;
+                match self.rest().chars().next() {
+                    Some(',') => self.pos += 1,
+                    Some('>') => {
+                        self.pos += 1;
+                        break;
+                    }
+                    _ => return Err(self.error("expected `,` or `>`")),
+                }
+            }
+            self.skip_trivia()?;
+        }
+        let uses_things_from = if !self.rest().starts_with('{') {
+            if self.identifier()? != "uses" {
+                return Err(self.error("expected `uses` or `{`"));
+            }
+            self.skip_trivia()?;
+            let s = self.identifier()?.to_string();
+            self.skip_trivia()?;
+            Some(s)
+        } else {
+            None
+        };
+        if !self.rest().starts_with('{') {
+            return Err(self.error("expected `{`"));
+        }
+        self.pos += 1;
+
+        let start = self.pos;
+        let mut structs = vec![];
+        let mut operators = vec![];
+        let mut nested = vec![];
+        loop {
+            let statement_start = self.pos;
+            let inner_doc = self.skip_trivia()?.join("\n");
+            if self.rest().is_empty() {
+                return Err(self.error("expected `}` before the end of the file"));
+            }
+            if self.rest().starts_with('}') {
+                self.pos += 1;
+                return Ok(BuiltinDeclaration {
+                    name,
+                    type_params,
+                    uses_things_from,
+                    doc,
+                    body: start..self.pos - 1,
+                    structs,
+                    operators,
+                    nested,
+                });
+            }
+            let keyword_start = self.pos;
+            match self.identifier().ok() {
+                Some("struct") => {
+                    structs.push(self.block(inner_doc)?);
+                    self.skip_trivia()?;
+                    if self.rest().starts_with(';') {
+                        self.pos += 1;
+                    }
+                    nested.push(statement_start..self.pos);
+                }
+                Some("operator") => {
+                    operators.push(self.operator(inner_doc)?);
+                    nested.push(statement_start..self.pos);
+                }
+                _ => {
+                    self.pos = keyword_start;
+                    self.skip_statement()?;
+                }
+            }
+        }
+    }
+
+    fn declarations(mut self) -> anyhow::Result<Vec<BuiltinDeclaration>> {
+        let mut ret = vec![];
+        loop {
+            let doc = self.skip_trivia()?.join("\n");
+            if self.rest().is_empty() {
+                return Ok(ret);
+            }
+            if self.identifier()? != "builtin" {
+                return Err(self.error("expected `builtin`"));
+            }
+            ret.push(self.block(doc)?);
+        }
+    }
+}
+
+impl BuiltinDeclarationFile {
+    fn produce(self, files: &mut Files) -> anyhow::Result<Vec<BuiltinTypeHir>> {
+        let declarations = DeclarationScanner {
+            filename: &self.filename,
+            text: &self.text,
+            pos: 0,
+        }
+        .declarations()?;
+        let file_index = files.add(File::new(
+            self.filename.clone(),
+            self.text.as_bytes().to_vec(),
+        ));
+        declarations
+            .into_iter()
+            .map(|d| {
+                d.produce(file_index, &self.filename, &self.text)
+                    .map_err(|e| anyhow::anyhow!(e.to_displayed_errors(files)))
+            })
+            .collect()
+    }
+}
+
+/// Whether `text` has nothing but whitespace, comments and stray semicolons after `pos`.
+fn only_trivia_after(text: &str, pos: usize) -> bool {
+    let mut scanner = DeclarationScanner {
+        filename: "",
+        text,
+        pos,
+    };
+    loop {
+        if scanner.skip_trivia().is_err() {
+            return false;
+        }
+        if !scanner.rest().starts_with(';') {
+            return scanner.rest().is_empty();
+        }
+        scanner.pos += 1;
+    }
+}
+
+impl BuiltinDeclaration {
+    fn produce(
+        self,
+        file_index: FileIndex,
+        filename: &str,
+        text: &str,
+    ) -> Result<BuiltinTypeHir, Vec<ParsingError>> {
+        // everything but the body is blanked out rather than cut, so that spans still point at the
+        // right place in the file
+        let mut body_only = String::with_capacity(text.len());
+        for (i, c) in text.char_indices() {
+            let visible = self.body.contains(&i) && !self.nested.iter().any(|n| n.contains(&i));
+            if visible || c == '\n' {
+                body_only.push(c);
+            } else {
+                body_only.extend(std::iter::repeat(' ').take(c.len_utf8()));
+            }
+        }
+
+        let mut parser = Parser::new(file_index, &body_only);
+        let mut errs = vec![];
+        let mut members = vec![];
+        let mut functions = vec![];
+        let mut constants = vec![];
+        let mut enums = vec![];
+        let mut parsed_to = self.body.start;
+        while let Some(inner) = parser.get_class_inner().map_err(|x| vec![x])? {
+            parsed_to = parsed_to.max(inner.span.get_end());
+            match inner.kind {
+                ast::ClassInnerKind::Declaration(ast::Declaration::Member(m)) => {
+                    let mut lowerer = HirLowerer::new(&mut errs);
+                    members.extend(
+                        lowerer
+                            .lower_member_declaration(m, false)
+                            .map(|def| MemberVariableHir { def })
+                            .collect_vec(),
+                    );
+                }
+                ast::ClassInnerKind::Declaration(ast::Declaration::Function(f)) => {
+                    let mut lowerer = HirLowerer::new(&mut errs);
+                    functions.push(FunctionHir {
+                        def: lowerer.lower_function_declaration(f),
+                    });
+                }
+                ast::ClassInnerKind::Const(c) => constants.push(ConstantHir { def: c }),
+                ast::ClassInnerKind::Enum(e) => enums.push(EnumHir { def: e }),
+                _ => errs.push(ParsingError {
+                    level: ParsingErrorLevel::Error,
+                    msg: "builtins can only contain member variables, functions, constants, enums \
+                          and structs"
+                        .to_string(),
+                    main_spans: vec1::vec1![inner.span],
+                    info_spans: vec![],
+                }),
+            }
+        }
+        // the parser stops at anything it can't read, which mustn't be taken as the end of the body
+        if !only_trivia_after(&body_only, parsed_to) {
+            if let Err(e) = parser.expect(None::<ast::ClassInner>, "a class inner") {
+                errs.push(e);
+            }
+        }
+        errs.extend(parser.to_errs());
+        if !errs.is_empty() {
+            return Err(errs);
+        }
+
+        Ok(BuiltinTypeHir {
+            filename: filename.to_string(),
+            name: self.name,
+            type_params: self.type_params,
+            doc: self.doc,
+            uses_things_from: self.uses_things_from,
+            members,
+            functions,
+            constants,
+            enums,
+            operators: self.operators,
+            structs: self
+                .structs
+                .into_iter()
+                .map(|s| s.produce(file_index, filename, text))
+                .collect::<Result<Vec<_>, _>>()?,
         })
     }
 }
@@ -204,33 +685,101 @@ impl ConstantFromFile {
     }
 }
 
+impl EnumFromFile {
+    fn produce(&self, files: &mut Files, filename: &str) -> Result<EnumHir, Vec<ParsingError>> {
+        let file = File::new(filename.to_string() + " enum", self.def.as_bytes().to_vec());
+        let file_index = files.add(file);
+        let (parsed, errs, err) = parse_inner(file_index, &self.def)?;
+        if !errs.is_empty() {
+            return Err(errs);
+        }
+        let e = match parsed.kind {
+            ast::ClassInnerKind::Enum(e) => e,
+            _ => {
+                return err("needed enum");
+            }
+        };
+        Ok(EnumHir { def: e })
+    }
+}
+
 impl BuiltinTypeHir {
     pub fn produce(
         &self,
         files: &mut Files,
         item_provider: &ItemProvider,
     ) -> crate::structures::Builtin {
-        let context = vec![zscript_parser::interner::intern_name(&self.name)];
+        self.produce_nested(files, item_provider, &[], None)
+    }
+
+    /// Produces a builtin nested inside another one, whose name is `parent_name` followed by this
+    /// builtin's name.
+    fn produce_nested(
+        &self,
+        files: &mut Files,
+        item_provider: &ItemProvider,
+        parent_context: &[NameSymbol],
+        parent_name: Option<&str>,
+    ) -> crate::structures::Builtin {
+        let context = crate::item::context_with(
+            parent_context,
+            zscript_parser::interner::intern_name(&self.name),
+        );
+        let name = match parent_name {
+            Some(p) => format!("{}.{}", p, self.name),
+            None => self.name.clone(),
+        };
+        let mut inner_enums = self
+            .enums
+            .iter()
+            .map(|e| {
+                let inner_name = files.text_from_span(e.def.name.span);
+                crate::document::enum_doc(
+                    &format!("{name}.{inner_name}"),
+                    inner_name,
+                    &context,
+                    &e.def,
+                    files,
+                    item_provider,
+                )
+            })
+            .collect_vec();
+        inner_enums.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+        let mut inner_structs = self
+            .structs
+            .iter()
+            .map(|s| s.produce_nested(files, item_provider, &context, Some(&name)))
+            .collect_vec();
+        inner_structs.sort_unstable_by(|a, b| a.name.cmp(&b.name));
         crate::structures::Builtin {
             context: context.clone(),
-            name: self.name.clone(),
+            name: name.clone(),
+            no_context_name: self.name.clone(),
+            type_params: self.type_params.clone(),
             filename: self.filename.clone(),
             doc_comment: self.doc.to_string(),
             variables: self
                 .members
                 .iter()
-                .map(|m| m.produce(self.name.clone(), files, item_provider, context.clone()))
+                .map(|m| m.produce(name.clone(), files, item_provider, context.clone()))
                 .collect_vec(),
             functions: self
                 .functions
                 .iter()
-                .map(|m| m.produce(self.name.clone(), files, item_provider, context.clone()))
+                .map(|m| m.produce(name.clone(), files, item_provider, context.clone()))
                 .collect_vec(),
             constants: self
                 .constants
                 .iter()
-                .map(|m| m.produce(self.name.clone(), files, item_provider, context.clone()))
+                .map(|m| m.produce(name.clone(), files, item_provider, context.clone()))
+                .collect_vec(),
+            operators: self
+                .operators
+                .iter()
+                .map(|o| o.produce(&self.filename, item_provider, context.clone()))
                 .collect_vec(),
+            inner_enums,
+            inner_structs,
         }
     }
 
@@ -239,6 +788,10 @@ impl BuiltinTypeHir {
         let mut funcs_to_add = vec![];
         let mut vars_to_add = vec![];
         let mut consts_to_add = vec![];
+        let mut enums_to_add = vec![];
+        for s in self.structs.iter_mut() {
+            s.extend_with_uses_things_from(hir)?;
+        }
         if let Some(n) = &self.uses_things_from {
             let ns = zscript_parser::interner::intern_name(n);
             let h = hir
@@ -265,6 +818,9 @@ impl BuiltinTypeHir {
                     zscript_parser::hir::StructInnerKind::Const(c) => {
                         consts_to_add.push(ConstantHir { def: c.clone() });
                     }
+                    zscript_parser::hir::StructInnerKind::Enum(e) => {
+                        enums_to_add.push(EnumHir { def: e.clone() });
+                    }
                     _ => {}
                 }
             }
@@ -274,10 +830,62 @@ impl BuiltinTypeHir {
         consts_to_add.sort_unstable_by_key(|x| x.def.span);
         self.functions.extend(funcs_to_add);
         self.members.extend(vars_to_add);
+        self.constants.extend(consts_to_add);
+        self.enums.extend(enums_to_add);
         Ok(())
     }
 }
 
+/// Escapes `text` for use in an HTML id, which can only hold letters, digits, `_`, `-` and `.`.
+/// `-` and `.` are escaped too, so that the parts of an id stay unambiguous.
+fn anchor_part(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            c if c.is_alphanumeric() || c == '_' => c.to_string(),
+            c => format!("-{:x}", c as u32),
+        })
+        .collect()
+}
+
+impl OperatorFromFile {
+    fn produce(
+        &self,
+        filename: &str,
+        item_provider: &ItemProvider,
+        context: Vec<NameSymbol>,
+    ) -> crate::structures::Operator {
+        let id = self
+            .lhs
+            .iter()
+            .chain([&self.symbol, &self.rhs])
+            .map(|p| anchor_part(p))
+            .join(".");
+        let name = match &self.lhs {
+            Some(lhs) => format!("{} {} {}", lhs, self.symbol, self.rhs),
+            // word operators need a space before their operand
+            None if self.symbol.ends_with(|c: char| c.is_alphanumeric()) => {
+                format!("{} {}", self.symbol, self.rhs)
+            }
+            None => format!("{}{}", self.symbol, self.rhs),
+        };
+        crate::structures::Operator {
+            def: crate::document::reconstruct_operator(
+                self.lhs.as_deref(),
+                &self.symbol,
+                &self.rhs,
+                &self.returns,
+                item_provider,
+                &context,
+            ),
+            context,
+            id,
+            name,
+            filename: filename.to_string(),
+            doc_comment: self.doc.clone(),
+        }
+    }
+}
+
 impl MemberVariableHir {
     fn produce(
         &self,
@@ -339,6 +947,7 @@ impl FunctionHir {
                 files,
             ),
             overrides: None,
+            overridden_by: vec![],
             deprecated: self
                 .def
                 .deprecated
@@ -359,7 +968,7 @@ impl ConstantHir {
     ) -> crate::structures::Constant {
         let owner = Owner::Builtin(owner_name);
         let const_to_add = crate::structures::Constant {
-            context,
+            context: context.clone(),
             name: files.text_from_span(self.def.name.span).to_string(),
             span: self.def.span,
             doc_comment: self
@@ -373,6 +982,12 @@ impl ConstantHir {
                 item_provider,
                 files,
             ),
+            value: crate::document::evaluated_value(
+                files.text_from_span(self.def.expr.span.unwrap()),
+                item_provider,
+                &context,
+            ),
+            elements: vec![],
         };
         const_to_add
     }
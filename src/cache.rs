@@ -0,0 +1,204 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use directories::ProjectDirs;
+
+use crate::cli::CacheCommand;
+
+/// The folder zscdoc caches git checkouts and unpacked archives in, which is created if needed.
+pub fn cache_dir() -> anyhow::Result<PathBuf> {
+    if let Some(proj_dirs) = ProjectDirs::from("", "zscdoc", "zscdoc") {
+        let cache_dir = proj_dirs.cache_dir();
+        let r = fs_extra::dir::create_all(cache_dir, false);
+        match &r {
+            Ok(()) => {}
+            Err(e) => match e.kind {
+                fs_extra::error::ErrorKind::AlreadyExists => {}
+                _ => {
+                    r.context("failed to make cache directory")?;
+                }
+            },
+        };
+        Ok(cache_dir.to_path_buf())
+    } else {
+        anyhow::bail!("Couldn't get a cache directory");
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum EntryKind {
+    Git,
+    Archive,
+}
+
+impl EntryKind {
+    fn folder_name(self) -> &'static str {
+        match self {
+            Self::Git => "checkouts",
+            Self::Archive => "archives",
+        }
+    }
+}
+
+/// A git checkout or unpacked archive in the cache.
+struct CacheEntry {
+    kind: EntryKind,
+    /// The name of the entry's folder, which is a hash of the git URL or archive contents.
+    name: String,
+    path: PathBuf,
+    /// The git URL or archive path the entry came from, if it's known.
+    source: Option<String>,
+    /// The commit a git checkout is at.
+    commit: Option<String>,
+}
+
+impl CacheEntry {
+    fn matches(&self, filter: &str) -> bool {
+        self.name.starts_with(filter) || self.source.as_deref() == Some(filter)
+    }
+}
+
+fn entries() -> anyhow::Result<Vec<CacheEntry>> {
+    let cache_dir = cache_dir()?;
+    let mut ret = vec![];
+    for kind in [EntryKind::Git, EntryKind::Archive] {
+        let folder = cache_dir.join(kind.folder_name());
+        if !folder.exists() {
+            continue;
+        }
+        for e in std::fs::read_dir(&folder).context("couldn't read cache directory")? {
+            let path = e?.path();
+            if !path.is_dir() {
+                continue;
+            }
+            let name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .context("cache entry names must be UTF-8")?
+                .to_string();
+            // an extraction that was interrupted, which is removed by the next one
+            if name.ends_with(".partial") {
+                continue;
+            }
+            let (source, commit) = match kind {
+                EntryKind::Git => match git2::Repository::open(&path) {
+                    Ok(repo) => (
+                        repo.find_remote("origin")
+                            .ok()
+                            .and_then(|r| r.url().map(|u| u.to_string())),
+                        repo.head()
+                            .ok()
+                            .and_then(|h| h.target())
+                            .map(|c| c.to_string()),
+                    ),
+                    Err(_) => (None, None),
+                },
+                EntryKind::Archive => (
+                    std::fs::read_to_string(folder.join(format!("{}.source", name))).ok(),
+                    None,
+                ),
+            };
+            ret.push(CacheEntry {
+                kind,
+                name,
+                path,
+                source,
+                commit,
+            });
+        }
+    }
+    Ok(ret)
+}
+
+fn list() -> anyhow::Result<()> {
+    use prettytable::*;
+
+    let entries = entries()?;
+    if entries.is_empty() {
+        println!("The cache is empty");
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table.add_row(row![
+        b->"Type",
+        b->"Name",
+        b->"Source",
+        b->"Commit",
+        b->"Size",
+    ]);
+
+    let mut total = 0;
+    for e in entries.iter() {
+        let size = fs_extra::dir::get_size(&e.path).unwrap_or(0);
+        total += size;
+        let name = &e.name[..12.min(e.name.len())];
+        let source = e.source.as_deref().unwrap_or("?");
+        let commit = e
+            .commit
+            .as_deref()
+            .map(|c| &c[..10.min(c.len())])
+            .unwrap_or("");
+        table.add_row(row![
+            format!("{:?}", e.kind),
+            name,
+            source,
+            commit,
+            format!("{:.1} MiB", size as f64 / (1024.0 * 1024.0)),
+        ]);
+    }
+
+    table.printstd();
+    println!("Total: {:.1} MiB", total as f64 / (1024.0 * 1024.0));
+    Ok(())
+}
+
+fn clean(filters: &[String]) -> anyhow::Result<()> {
+    let entries = entries()?;
+    // archive sources are recorded as canonical paths, so paths are made canonical to match
+    let canonical_filters = filters
+        .iter()
+        .map(|f| match std::fs::canonicalize(f) {
+            Ok(p) => p.to_string_lossy().into_owned(),
+            Err(_) => f.clone(),
+        })
+        .collect::<Vec<_>>();
+    let to_remove = entries
+        .iter()
+        .filter(|e| filters.is_empty() || canonical_filters.iter().any(|f| e.matches(f)))
+        .collect::<Vec<_>>();
+    if let Some((f, _)) = filters
+        .iter()
+        .zip(canonical_filters.iter())
+        .find(|(_, c)| !entries.iter().any(|e| e.matches(c)))
+    {
+        anyhow::bail!("nothing in the cache matches {:?}", f);
+    }
+
+    for e in to_remove.iter() {
+        eprintln!(
+            "Removing {} ({})",
+            e.source.as_deref().unwrap_or(&e.name),
+            e.kind.folder_name()
+        );
+        std::fs::remove_dir_all(&e.path).context(format!("couldn't remove {:?}", e.path))?;
+        if let EntryKind::Archive = e.kind {
+            let source_file = e.path.with_file_name(format!("{}.source", e.name));
+            if source_file.exists() {
+                std::fs::remove_file(source_file)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn run(command: &CacheCommand) -> anyhow::Result<()> {
+    match command {
+        CacheCommand::List => list(),
+        CacheCommand::Clean { entries } => clean(entries),
+        CacheCommand::Path => {
+            println!("{}", cache_dir()?.display());
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,250 @@
+use std::{collections::HashMap, path::Path};
+
+use anyhow::Context;
+use itertools::Itertools;
+use zscript_parser::filesystem::GZDoomFolderFileSystem;
+
+use crate::{archive, git::GitRef, option_vec_to_vec, Assets, Config};
+
+/// Every key a `[[dependency]]` can have. serde can't report unknown keys inside a flattened
+/// field, so these are checked by hand.
+const DEPENDENCY_KEYS: &[&str] = &[
+    "path",
+    "git",
+    "refname",
+    "tag",
+    "rev",
+    "base",
+    "inventory",
+    "url",
+];
+
+/// Files always written to the output folder, besides the bundled assets.
+const GENERATED_FILES: &[&str] = &[
+    "index.html",
+    "search.json",
+    "search.js",
+    "search.json.gz",
+    "search.js.gz",
+    "fulltext.json",
+    "fulltext.js",
+    "fulltext.json.gz",
+    "fulltext.js.gz",
+    "items.json",
+    "favicon.png",
+    "sitemap.xml",
+    "robots.txt",
+];
+
+/// Prefixes of the pages generated for items, which depend on the code being documented.
+const GENERATED_PAGE_PREFIXES: &[&str] = &["class.", "struct.", "enum.", "builtin."];
+
+/// Keeps track of what writes each file of the output folder.
+#[derive(Default)]
+struct OutputFiles {
+    written_by: HashMap<String, String>,
+}
+
+impl OutputFiles {
+    fn claim(&mut self, filename: &str, by: String, problems: &mut Vec<String>) {
+        if filename == "search" || filename.starts_with("search/") {
+            problems.push(format!(
+                "{} writes {:?}, which is where the search index goes",
+                by, filename
+            ));
+        }
+        if filename.ends_with(".html")
+            && GENERATED_PAGE_PREFIXES
+                .iter()
+                .any(|p| filename.starts_with(p))
+        {
+            problems.push(format!(
+                "{} writes {:?}, which could be overwritten by the page of an item",
+                by, filename
+            ));
+        }
+        match self.written_by.get(filename) {
+            Some(other) if *other != by => problems.push(format!(
+                "both {} and {} write {:?}, so one overwrites the other",
+                other, by, filename
+            )),
+            Some(_) => {}
+            None => {
+                self.written_by.insert(filename.to_string(), by);
+            }
+        }
+    }
+}
+
+/// Prints a JSON schema of `zscdoc.toml`, for editors that can use one for autocompletion.
+pub fn print_schema() -> anyhow::Result<()> {
+    let schema = schemars::schema_for!(Config);
+    println!("{}", serde_json::to_string_pretty(&schema)?);
+    Ok(())
+}
+
+/// Checks the `zscdoc.toml` of the archive at `folder` more strictly than a normal build does,
+/// printing every problem found.
+pub fn check(folder: &str) -> anyhow::Result<()> {
+    let archive_paths = archive::unpack(Path::new(folder)).context("loading archive")?;
+    let folder = archive_paths
+        .folder
+        .to_str()
+        .context("paths must be UTF-8")?;
+    let mut filesystem = GZDoomFolderFileSystem::new(folder.to_string(), folder.to_string())
+        .context("couldn't load a path")?;
+    let config_text = filesystem
+        .get_file("docs/zscdoc.toml")
+        .context("couldn't find zscdoc.toml")?
+        .text()
+        .to_string();
+
+    let mut problems = vec![];
+
+    // checked before deserializing into `Config`, since a misspelt dependency key only makes that
+    // fail with "data did not match any variant"
+    let raw: toml::Value = toml::from_str(&config_text).context("config file parsing failed")?;
+    let raw_dependencies = raw
+        .get("dependency")
+        .and_then(|d| d.as_array())
+        .map(|d| d.as_slice())
+        .unwrap_or_default();
+    for (i, dependency) in raw_dependencies.iter().enumerate() {
+        let Some(table) = dependency.as_table() else {
+            continue;
+        };
+        for key in table.keys() {
+            if !DEPENDENCY_KEYS.contains(&key.as_str()) {
+                problems.push(format!("unknown key `dependency.{}.{}`", i, key));
+            }
+        }
+        let sources = ["path", "git", "inventory"]
+            .into_iter()
+            .filter(|k| table.contains_key(*k))
+            .collect_vec();
+        if sources.len() != 1 {
+            problems.push(format!(
+                "dependency {} needs exactly one of `path`, `git` or `inventory`",
+                i
+            ));
+        }
+    }
+
+    let mut unknown_keys = vec![];
+    match serde_ignored::deserialize(&mut toml::Deserializer::new(&config_text), |path| {
+        unknown_keys.push(path.to_string())
+    }) {
+        Ok(config) => {
+            problems.extend(
+                unknown_keys
+                    .into_iter()
+                    .map(|k| format!("unknown key `{}`", k)),
+            );
+            check_parsed(
+                config,
+                &archive_paths.relative_to,
+                &mut filesystem,
+                &mut problems,
+            );
+        }
+        Err(e) => problems.push(format!("config file parsing failed: {}", e)),
+    }
+
+    if problems.is_empty() {
+        println!("zscdoc.toml looks good");
+        Ok(())
+    } else {
+        for p in problems.iter() {
+            println!("- {}", p);
+        }
+        anyhow::bail!("found {} problems in zscdoc.toml", problems.len());
+    }
+}
+
+/// The checks that need the config to have been deserialized.
+fn check_parsed(
+    config: Config,
+    relative_to: &Path,
+    filesystem: &mut GZDoomFolderFileSystem,
+    problems: &mut Vec<String>,
+) {
+    for (i, dependency) in option_vec_to_vec(config.dependency).iter().enumerate() {
+        match &dependency.find_at {
+            crate::DependencyPathKind::Path { path } => {
+                if !relative_to.join(path).exists() {
+                    problems.push(format!("dependency {} path {:?} doesn't exist", i, path));
+                }
+            }
+            crate::DependencyPathKind::Inventory { inventory } => {
+                if !relative_to.join(inventory).exists() {
+                    problems.push(format!(
+                        "dependency {} inventory {:?} doesn't exist",
+                        i, inventory
+                    ));
+                }
+            }
+            crate::DependencyPathKind::Git {
+                refname, tag, rev, ..
+            } => {
+                if let Err(e) =
+                    GitRef::from_options(refname.as_deref(), tag.as_deref(), rev.as_deref())
+                {
+                    problems.push(format!("dependency {}: {}", i, e));
+                }
+            }
+        }
+    }
+
+    let archive_config = config.archive;
+    let mut check_file = |filename: &str, what: &str, problems: &mut Vec<String>| {
+        let filename_to_get = format!("docs/{}", filename);
+        if filesystem.get_file(&filename_to_get).is_none() {
+            problems.push(format!("{} {:?} doesn't exist", what, filename_to_get));
+        }
+    };
+
+    let mut outputs = OutputFiles::default();
+    for generated in GENERATED_FILES {
+        outputs.claim(generated, "zscdoc".to_string(), problems);
+    }
+    for asset in Assets::iter() {
+        outputs.claim(&asset, "zscdoc".to_string(), problems);
+    }
+
+    for m in option_vec_to_vec(archive_config.markdown_files) {
+        check_file(&m.filename, "markdown file", problems);
+        match m.filename.strip_suffix(".md") {
+            Some(s) => outputs.claim(
+                &format!("{}.html", s),
+                format!("markdown file {:?}", m.filename),
+                problems,
+            ),
+            None => problems.push(format!(
+                "markdown file {:?} doesn't have extension .md",
+                m.filename
+            )),
+        }
+    }
+    for b in option_vec_to_vec(archive_config.builtins) {
+        check_file(&b, "builtin file", problems);
+    }
+    let copied = option_vec_to_vec(archive_config.copy_files)
+        .into_iter()
+        .map(|c| (c.filename, "copied file"))
+        .chain(archive_config.site_image.map(|s| (s, "site image")))
+        .chain(
+            option_vec_to_vec(archive_config.theme.stylesheets)
+                .into_iter()
+                .map(|s| (s, "stylesheet")),
+        )
+        .chain(
+            option_vec_to_vec(archive_config.theme.scripts)
+                .into_iter()
+                .map(|s| (s, "script")),
+        );
+    for (filename, what) in copied {
+        check_file(&filename, what, problems);
+        // the same file can be both copied and used as a stylesheet, which is harmless
+        outputs.claim(&filename, "a copied file".to_string(), problems);
+    }
+}
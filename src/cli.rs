@@ -1,4 +1,4 @@
-use clap::{ArgEnum, ArgGroup, Parser};
+use clap::{ArgEnum, ArgGroup, Parser, Subcommand};
 
 #[derive(ArgEnum, Debug, Clone, Copy)]
 pub enum CoverageLevel {
@@ -7,12 +7,26 @@ pub enum CoverageLevel {
     Verbose,
 }
 
+#[derive(ArgEnum, Debug, Clone, Copy)]
+pub enum OutputFormat {
+    Html,
+    Markdown,
+    Mediawiki,
+}
+
 #[derive(Parser, Debug)]
 #[clap(author, version, about = "zscript documentation generator", long_about = None)]
-#[clap(group(ArgGroup::new("mode").required(true)))]
+#[clap(group(ArgGroup::new("mode")))]
 pub struct Args {
-    #[clap(short, long, help = "Path to the folder to document")]
-    pub folder: String,
+    #[clap(subcommand)]
+    pub command: Option<Command>,
+
+    #[clap(
+        short,
+        long,
+        help = "Path to the folder, or .pk3/.pk7/.zip file, to document - needed for everything except `cache`"
+    )]
+    pub folder: Option<String>,
 
     #[clap(short, long, help = "Path for the output folder", group = "mode")]
     pub output: Option<String>,
@@ -25,6 +39,14 @@ pub struct Args {
     )]
     pub coverage: Option<CoverageLevel>,
 
+    #[clap(
+        long,
+        arg_enum,
+        default_value = "html",
+        help = "The format to write the documentation in - markdown is laid out for GitHub wikis, and mediawiki for wikis such as the ZDoom wiki"
+    )]
+    pub format: OutputFormat,
+
     #[clap(
         long,
         help = "Deletes the target folder without confirmation. Best kept off in most cases."
@@ -49,6 +71,94 @@ pub struct Args {
     )]
     pub target_version: Option<String>,
 
-    #[clap(long, help = "The canonical domain you're hosting the docs on")]
+    #[clap(
+        long,
+        help = "The canonical domain you're hosting the docs on, e.g. https://example.com - used for canonical links and sitemap.xml"
+    )]
     pub canonical_domain: Option<String>,
+
+    #[clap(
+        long,
+        help = "Generates documentation that can be browsed straight from disk without a web server - links are made relative and the search index is emitted as a script"
+    )]
+    pub offline: bool,
+
+    #[clap(
+        long,
+        help = "Moves git dependencies to the latest commit of their branch or tag, instead of the commit recorded in docs/zscdoc.lock"
+    )]
+    pub update_deps: bool,
+
+    #[clap(
+        long,
+        conflicts_with = "update_deps",
+        help = "Builds git dependencies from the checkouts already in the cache without touching the network (named so as not to clash with `--offline`, which is about the generated HTML)"
+    )]
+    pub offline_deps: bool,
+
+    #[clap(
+        long,
+        help = "Also writes gzip-compressed copies of the search index files, for web servers that can serve precompressed files"
+    )]
+    pub compress_search_index: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    #[clap(about = "Prints the signature and documentation of an item in the terminal")]
+    Show {
+        #[clap(help = "The path to the item, e.g. `Actor.Destroy`")]
+        path: String,
+
+        #[clap(long, help = "Only prints the URL of the item's documentation")]
+        url: bool,
+    },
+
+    #[clap(
+        about = "Runs a language server on stdin and stdout, providing hover docs, go-to-definition and workspace symbols"
+    )]
+    Lsp,
+
+    #[clap(about = "Writes a starter docs/zscdoc.toml and docs/summary.md for the folder")]
+    Init {
+        #[clap(
+            long,
+            help = "The name of the archive - worked out from the folder names if not given"
+        )]
+        name: Option<String>,
+    },
+
+    #[clap(
+        about = "Checks zscdoc.toml for unknown keys, missing files and output files that overwrite each other"
+    )]
+    CheckConfig {
+        #[clap(
+            long,
+            help = "Prints a JSON schema of zscdoc.toml for editor autocompletion instead of checking it"
+        )]
+        schema: bool,
+    },
+
+    #[clap(about = "Inspects or prunes the cache of git checkouts and unpacked archives")]
+    Cache {
+        #[clap(subcommand)]
+        action: CacheCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CacheCommand {
+    #[clap(about = "Lists the cached git checkouts and unpacked archives")]
+    List,
+
+    #[clap(about = "Deletes entries from the cache")]
+    Clean {
+        #[clap(
+            help = "Git URLs, archive paths or entry names (or the start of them) to delete - everything is deleted if none are given"
+        )]
+        entries: Vec<String>,
+    },
+
+    #[clap(about = "Prints the path of the cache folder")]
+    Path,
 }
@@ -0,0 +1,225 @@
+//! Evaluation of the constant expressions used for enumerator and constant values.
+//!
+//! This works on the source text of the expressions, and only understands the arithmetic that
+//! constants use in practice. Anything else evaluates to `None` rather than a wrong value.
+
+use std::fmt::Display;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Value {
+    Int(i64),
+    Float(f64),
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Int(i) => write!(f, "{} ({})", i, int_hex(*i)),
+            Self::Float(x) => write!(f, "{:?}", x),
+        }
+    }
+}
+
+impl Value {
+    fn as_float(self) -> f64 {
+        match self {
+            Self::Int(i) => i as f64,
+            Self::Float(x) => x,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token<'a> {
+    Value(Value),
+    /// A name, which can be a dotted chain like `Actor.DEFAULT_HEALTH`.
+    Ident(&'a str),
+    Punct(&'a str),
+}
+
+/// Punctuation, longest first so that `<<` isn't read as two `<`s.
+const PUNCTUATION: &[&str] = &[
+    "<<", ">>", "+", "-", "*", "/", "%", "&", "|", "^", "~", "(", ")",
+];
+
+fn parse_int(literal: &str) -> Option<i64> {
+    let literal = literal.trim_end_matches(['u', 'U', 'l', 'L']);
+    if let Some(hex) = literal
+        .strip_prefix("0x")
+        .or_else(|| literal.strip_prefix("0X"))
+    {
+        i64::from_str_radix(hex, 16).ok()
+    } else if literal.len() > 1 && literal.starts_with('0') {
+        i64::from_str_radix(&literal[1..], 8).ok()
+    } else {
+        literal.parse().ok()
+    }
+}
+
+fn word_len(text: &str) -> usize {
+    text.find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(text.len())
+}
+
+fn parse_float(literal: &str) -> Option<f64> {
+    literal.trim_end_matches(['f', 'F']).parse().ok()
+}
+
+fn tokenize(text: &str) -> Option<Vec<Token>> {
+    let mut tokens = vec![];
+    let mut rest = text.trim_start();
+    while !rest.is_empty() {
+        let c = rest.chars().next()?;
+        let len = if c.is_ascii_digit() {
+            let mut len = word_len(rest);
+            if rest[len..].starts_with('.') {
+                len += 1 + word_len(&rest[len + 1..]);
+                // the exponent's sign isn't part of the word
+                if rest[..len].ends_with(['e', 'E']) && rest[len..].starts_with(['+', '-']) {
+                    len += 1 + word_len(&rest[len + 1..]);
+                }
+                tokens.push(Token::Value(Value::Float(parse_float(&rest[..len])?)));
+            } else {
+                tokens.push(Token::Value(Value::Int(parse_int(&rest[..len])?)));
+            }
+            len
+        } else if c.is_alphanumeric() || c == '_' {
+            let mut len = word_len(rest);
+            while rest[len..].starts_with('.') && word_len(&rest[len + 1..]) > 0 {
+                len += 1 + word_len(&rest[len + 1..]);
+            }
+            tokens.push(Token::Ident(&rest[..len]));
+            len
+        } else {
+            let p = PUNCTUATION.iter().find(|p| rest.starts_with(**p))?;
+            tokens.push(Token::Punct(*p));
+            p.len()
+        };
+        rest = rest[len..].trim_start();
+    }
+    Some(tokens)
+}
+
+fn binary_precedence(op: &str) -> Option<u8> {
+    Some(match op {
+        "|" => 1,
+        "^" => 2,
+        "&" => 3,
+        "<<" | ">>" => 4,
+        "+" | "-" => 5,
+        "*" | "/" | "%" => 6,
+        _ => return None,
+    })
+}
+
+struct Evaluator<'a, F> {
+    tokens: &'a [Token<'a>],
+    pos: usize,
+    lookup: F,
+}
+
+impl<'a, F: Fn(&str) -> Option<Value>> Evaluator<'a, F> {
+    fn next(&mut self) -> Option<Token<'a>> {
+        let t = self.tokens.get(self.pos).copied();
+        self.pos += 1;
+        t
+    }
+
+    fn unary(&mut self) -> Option<Value> {
+        match self.next()? {
+            Token::Value(v) => Some(v),
+            Token::Ident(name) => (self.lookup)(name),
+            Token::Punct("(") => {
+                let v = self.binary(0)?;
+                (self.next()? == Token::Punct(")")).then_some(v)
+            }
+            Token::Punct("-") => match self.unary()? {
+                Value::Int(i) => i.checked_neg().map(Value::Int),
+                Value::Float(x) => Some(Value::Float(-x)),
+            },
+            Token::Punct("+") => self.unary(),
+            Token::Punct("~") => match self.unary()? {
+                Value::Int(i) => Some(Value::Int(!i)),
+                Value::Float(_) => None,
+            },
+            Token::Punct(_) => None,
+        }
+    }
+
+    /// Evaluates operators binding tighter than `min_precedence`, by precedence climbing.
+    fn binary(&mut self, min_precedence: u8) -> Option<Value> {
+        let mut lhs = self.unary()?;
+        while let Some(Token::Punct(op)) = self.tokens.get(self.pos).copied() {
+            let precedence = match binary_precedence(op) {
+                Some(p) if p > min_precedence => p,
+                _ => break,
+            };
+            self.pos += 1;
+            let rhs = self.binary(precedence)?;
+            lhs = binary_op(op, lhs, rhs)?;
+        }
+        Some(lhs)
+    }
+}
+
+fn binary_op(op: &str, lhs: Value, rhs: Value) -> Option<Value> {
+    Some(match (lhs, rhs) {
+        (Value::Int(lhs), Value::Int(rhs)) => Value::Int(match op {
+            "|" => lhs | rhs,
+            "^" => lhs ^ rhs,
+            "&" => lhs & rhs,
+            "<<" => lhs.checked_shl(rhs.try_into().ok()?)?,
+            ">>" => lhs.checked_shr(rhs.try_into().ok()?)?,
+            "+" => lhs.checked_add(rhs)?,
+            "-" => lhs.checked_sub(rhs)?,
+            "*" => lhs.checked_mul(rhs)?,
+            "/" => lhs.checked_div(rhs)?,
+            "%" => lhs.checked_rem(rhs)?,
+            _ => unreachable!(),
+        }),
+        // mixing in a float makes the whole operation a float one, which has no bitwise operators
+        _ => {
+            let (lhs, rhs) = (lhs.as_float(), rhs.as_float());
+            Value::Float(match op {
+                "+" => lhs + rhs,
+                "-" => lhs - rhs,
+                "*" => lhs * rhs,
+                "/" => lhs / rhs,
+                "%" => lhs % rhs,
+                _ => return None,
+            })
+        }
+    })
+}
+
+/// Evaluates a constant expression, using `lookup` for the values of any names in it. Integer
+/// results wrap like ZScript's 32-bit `int`.
+pub fn eval(text: &str, lookup: impl Fn(&str) -> Option<Value>) -> Option<Value> {
+    let tokens = tokenize(text)?;
+    let mut evaluator = Evaluator {
+        tokens: &tokens,
+        pos: 0,
+        lookup,
+    };
+    let v = evaluator.binary(0)?;
+    if evaluator.pos != tokens.len() {
+        return None;
+    }
+    Some(match v {
+        Value::Int(i) => Value::Int(i as i32 as i64),
+        Value::Float(x) => Value::Float(x),
+    })
+}
+
+/// Evaluates an integer constant expression, like an enumerator's value.
+pub fn eval_int(text: &str, lookup: impl Fn(&str) -> Option<i64>) -> Option<i64> {
+    match eval(text, |n| lookup(n).map(Value::Int))? {
+        Value::Int(i) => Some(i),
+        Value::Float(_) => None,
+    }
+}
+
+/// Formats a value as hex the way it'd be written for a 32-bit `int`, so `-1` is `0xFFFFFFFF`.
+pub fn int_hex(v: i64) -> String {
+    format!("0x{:X}", v as i32 as u32)
+}
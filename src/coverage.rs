@@ -15,6 +15,7 @@ pub enum CoverageKind {
     Member,
     Enumerator,
     Constant,
+    Operator,
     Property,
     Flag,
 }
@@ -201,7 +202,7 @@ impl Struct {
         .into_iter()
         .chain(cov_field!(self.public.variables, context, files))
         .chain(cov_field!(self.public.functions, context, files))
-        .chain(cov_field!(self.protected.functions, context, files))
+        .chain(cov_field!(self.protected.variables, context, files))
         .chain(cov_field!(self.protected.functions, context, files))
         .chain(cov_field!(self.inner_enums, context, files))
         .chain(cov_field!(self.constants, context, files))
@@ -243,6 +244,13 @@ impl Builtin {
         .chain(cov_field!(self.variables, context, files))
         .chain(cov_field!(self.functions, context, files))
         .chain(cov_field!(self.constants, context, files))
+        .chain(cov_field!(self.operators, context, files))
+        .chain(cov_field!(self.inner_enums, context, files))
+        .chain(self.inner_structs.iter().flat_map(
+            move |s| -> Box<dyn Iterator<Item = CoverageItem> + 'a> {
+                Box::new(s.coverage(&[], files))
+            },
+        ))
     }
 }
 
@@ -263,6 +271,23 @@ impl Constant {
     }
 }
 
+impl Operator {
+    pub fn coverage(
+        &self,
+        context: &[String],
+        _files: &Files,
+    ) -> impl Iterator<Item = CoverageItem> {
+        let context = context_with(context, &self.name);
+        Some(CoverageItem {
+            covered: !self.doc_comment.is_empty(),
+            kind: CoverageKind::Operator,
+            filename: self.filename.to_string(),
+            path: context,
+        })
+        .into_iter()
+    }
+}
+
 impl MemberVariable {
     pub fn coverage(
         &self,
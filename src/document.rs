@@ -5,7 +5,7 @@ use crate::{
 use zscript_parser::{
     filesystem::Files,
     hir,
-    interner::{NameSymbol, StringSymbol},
+    interner::{intern_name, NameSymbol, StringSymbol},
     ir_common,
 };
 
@@ -119,6 +119,17 @@ fn add_type_if_possible<T, U>(
     }
 }
 
+/// Adds the name of a builtin container like `Array`, linking it to the container's builtin page
+/// if there is one. This ignores the context, so that a type with the same name nested in a class
+/// doesn't get linked instead.
+fn add_container_to_source(
+    name: &str,
+    item_provider: &ItemProvider,
+    source: &mut SourceCodeWithLinks,
+) {
+    add_type_if_possible(name, [intern_name(name)], item_provider, &[], false, source);
+}
+
 fn add_type_to_source(
     ty: &hir::Type,
     item_provider: &ItemProvider,
@@ -210,7 +221,8 @@ fn add_type_to_source(
         }
         hir::Type::Map(b) => {
             let (k, v) = &**b;
-            source.add_no_link("Map< ");
+            add_container_to_source("Map", item_provider, source);
+            source.add_no_link("< ");
             add_type_to_source(k, item_provider, context, source, files);
             source.add_no_link(", ");
             add_type_to_source(v, item_provider, context, source, files);
@@ -218,7 +230,8 @@ fn add_type_to_source(
         }
         hir::Type::MapIterator(b) => {
             let (k, v) = &**b;
-            source.add_no_link("MapIterator< ");
+            add_container_to_source("MapIterator", item_provider, source);
+            source.add_no_link("< ");
             add_type_to_source(k, item_provider, context, source, files);
             source.add_no_link(", ");
             add_type_to_source(v, item_provider, context, source, files);
@@ -251,7 +264,8 @@ fn add_type_to_source(
             }
         }
         hir::Type::DynArray(d) => {
-            source.add_no_link("Array< ");
+            add_container_to_source("Array", item_provider, source);
+            source.add_no_link("< ");
             add_type_to_source(d, item_provider, context, source, files);
             source.add_no_link(" >");
         }
@@ -505,6 +519,44 @@ pub fn reconstruct_constant_declaration(
     ret
 }
 
+/// Reconstructs an operator of a builtin type as `Lhs symbol Rhs -> Returns`, linking any type
+/// names that resolve.
+pub fn reconstruct_operator(
+    lhs: Option<&str>,
+    symbol: &str,
+    rhs: &str,
+    returns: &str,
+    item_provider: &ItemProvider,
+    context: &[NameSymbol],
+) -> SourceCodeWithLinks {
+    let mut ret = SourceCodeWithLinks { sections: vec![] };
+    let add_type = |ty: &str, ret: &mut SourceCodeWithLinks| {
+        add_type_if_possible(
+            ty,
+            ty.split('.').map(intern_name),
+            item_provider,
+            context,
+            false,
+            ret,
+        )
+    };
+    match lhs {
+        Some(lhs) => {
+            add_type(lhs, &mut ret);
+            ret.add_no_link(&format!(" {} ", symbol));
+        }
+        // word operators need a space before their operand
+        None if symbol.ends_with(|c: char| c.is_alphanumeric()) => {
+            ret.add_no_link(&format!("{} ", symbol))
+        }
+        None => ret.add_no_link(symbol),
+    }
+    add_type(rhs, &mut ret);
+    ret.add_no_link(" -> ");
+    add_type(returns, &mut ret);
+    ret
+}
+
 fn reconstruct_static_const_array_declaration(
     owner: Owner,
     sca: &hir::StaticConstArray,
@@ -522,22 +574,46 @@ fn reconstruct_static_const_array_declaration(
         text: name.clone(),
         kind: LinkedSectionKind::Constant { owner, link: name },
     });
-    ret.add_no_link(" = {");
-    ret.add_newline_indent();
-    let mut first = true;
-    for e in sca.exprs.list.iter() {
-        if !first {
-            ret.add_no_link(",");
-            ret.add_newline_indent_or_spacing();
-        }
-        first = false;
-        ret.add_no_link(files.text_from_span(e.span.unwrap()));
-    }
-    ret.add_newline_no_indent();
-    ret.add_no_link("}");
     ret
 }
 
+/// The value of a constant expression, if it can be evaluated to something that isn't just the
+/// expression itself.
+pub fn evaluated_value(
+    expr: &str,
+    item_provider: &ItemProvider,
+    context: &[NameSymbol],
+) -> Option<String> {
+    let value = item_provider.eval_constant(expr, context)?;
+    let is_literal = match value {
+        crate::consteval::Value::Int(i) => expr.trim() == i.to_string(),
+        crate::consteval::Value::Float(x) => {
+            expr.trim().trim_end_matches(['f', 'F']).parse::<f64>().ok() == Some(x)
+        }
+    };
+    (!is_literal).then(|| value.to_string())
+}
+
+/// The elements of a static const array, with their values where they can be evaluated.
+fn static_const_array_elements(
+    sca: &hir::StaticConstArray,
+    item_provider: &ItemProvider,
+    context: &[NameSymbol],
+    files: &Files,
+) -> Vec<ArrayElement> {
+    sca.exprs
+        .list
+        .iter()
+        .map(|e| {
+            let expr = files.text_from_span(e.span.unwrap());
+            ArrayElement {
+                expr: expr.to_string(),
+                value: evaluated_value(expr, item_provider, context),
+            }
+        })
+        .collect()
+}
+
 fn reconstruct_property(
     owner: Owner,
     prop: &ir_common::PropertyDefinition,
@@ -625,10 +701,152 @@ fn class_flag_to_string(flag: hir::ClassDefinitionFlags) -> &'static str {
     }
 }
 
+/// The class `c` is derived from, if it's known.
+fn class_ancestor<'a>(
+    hir: &'a hir::TopLevel,
+    c: &hir::ClassDefinition,
+) -> Option<&'a hir::ClassDefinition> {
+    hir.definitions
+        .get(&c.ancestor?.symbol)?
+        .iter()
+        .find_map(|t| match &t.kind {
+            hir::TopLevelDefinitionKind::Class(c) => Some(c),
+            _ => None,
+        })
+}
+
+/// `c` followed by each of its ancestors, stopping if the inheritance goes round in a circle.
+fn class_and_ancestors<'a>(
+    hir: &'a hir::TopLevel,
+    c: &'a hir::ClassDefinition,
+) -> Vec<&'a hir::ClassDefinition> {
+    let mut ret = vec![c];
+    while let Some(a) = class_ancestor(hir, ret[ret.len() - 1]) {
+        if ret.iter().any(|x| x.name.symbol == a.name.symbol) {
+            break;
+        }
+        ret.push(a);
+    }
+    ret
+}
+
+fn class_function<'a>(
+    c: &'a hir::ClassDefinition,
+    name: NameSymbol,
+) -> Option<&'a hir::FunctionDeclaration> {
+    c.inners.get(&name)?.iter().find_map(|i| match &i.kind {
+        hir::ClassInnerKind::FunctionDeclaration(f) => Some(f),
+        _ => None,
+    })
+}
+
+/// A link to the function `f` of the class `c`, shown with the class name.
+fn class_function_link(
+    c: &hir::ClassDefinition,
+    f: &hir::FunctionDeclaration,
+    files: &Files,
+    item_provider: &ItemProvider,
+) -> Option<LinkedSection> {
+    // the class is looked up rather than the function, since a class's items include those it
+    // inherits, and only the class says which archive it's documented in
+    let class = item_provider.resolve(&[], [c.name.symbol])?.pop()?;
+    let class_name = files.text_from_span(c.name.span);
+    let func_name = files.text_from_span(f.name.span);
+    Some(LinkedSection {
+        link_prefix: class.link_prefix.clone(),
+        text: format!("{}.{}", class_name, func_name),
+        kind: LinkedSectionKind::Function {
+            owner: Owner::Class(vec![class_name.to_string()]),
+            link: func_name.to_string(),
+        },
+    })
+}
+
+/// The classes derived directly from each class, by the name of the class they derive from.
+type Subclasses<'a> = std::collections::HashMap<NameSymbol, Vec<&'a hir::ClassDefinition>>;
+
+fn subclass_map(hir: &hir::TopLevel) -> Subclasses<'_> {
+    let mut ret = Subclasses::new();
+    let classes = hir
+        .definitions
+        .values()
+        .flat_map(|d| d.iter())
+        .filter_map(|t| match &t.kind {
+            hir::TopLevelDefinitionKind::Class(c) => Some(c),
+            _ => None,
+        });
+    for c in classes {
+        if let Some(a) = class_ancestor(hir, c) {
+            ret.entry(a.name.symbol).or_default().push(c);
+        }
+    }
+    ret
+}
+
+/// The functions of subclasses of `c` that override its virtual or abstract function `f`.
+fn overridden_by(
+    subclasses: &Subclasses,
+    c: &hir::ClassDefinition,
+    f: &hir::FunctionDeclaration,
+    files: &Files,
+    item_provider: &ItemProvider,
+) -> Vec<LinkedSection> {
+    let mut ret = vec![];
+    // the inheritance going round in a circle is an error, but it shouldn't hang
+    let mut seen = std::collections::HashSet::from([c.name.symbol]);
+    let mut to_visit = vec![c.name.symbol];
+    while let Some(class) = to_visit.pop() {
+        for sub in subclasses.get(&class).into_iter().flatten() {
+            if !seen.insert(sub.name.symbol) {
+                continue;
+            }
+            to_visit.push(sub.name.symbol);
+            let Some(func) = class_function(sub, f.name.symbol) else {
+                continue;
+            };
+            if func.flags.contains(hir::FunctionFlags::OVERRIDE)
+                && !should_skip(func.doc_comment.as_ref())
+            {
+                ret.extend(class_function_link(sub, func, files, item_provider));
+            }
+        }
+    }
+    ret.sort_unstable_by(|a, b| a.text.cmp(&b.text));
+    ret
+}
+
+/// The abstract functions that a subclass of the abstract class `c` has to override, which are
+/// those whose most derived declaration is abstract.
+fn required_overrides(
+    hir: &hir::TopLevel,
+    c: &hir::ClassDefinition,
+    files: &Files,
+    item_provider: &ItemProvider,
+) -> Vec<LinkedSection> {
+    let mut seen = std::collections::HashSet::new();
+    let mut ret = vec![];
+    for class in class_and_ancestors(hir, c) {
+        for (name, d) in class.inners.iter() {
+            let Some(f) = d.iter().find_map(|i| match &i.kind {
+                hir::ClassInnerKind::FunctionDeclaration(f) => Some(f),
+                _ => None,
+            }) else {
+                continue;
+            };
+            if seen.insert(*name) && f.flags.contains(hir::FunctionFlags::ABSTRACT) {
+                ret.extend(class_function_link(class, f, files, item_provider));
+            }
+        }
+    }
+    ret.sort_unstable_by(|a, b| a.text.cmp(&b.text));
+    ret
+}
+
 fn class_doc(
     name: &str,
     context: &[NameSymbol],
     hir: &hir::TopLevel,
+    subclasses: &Subclasses,
     c: &hir::ClassDefinition,
     files: &Files,
     item_provider: &ItemProvider,
@@ -697,6 +915,11 @@ fn class_doc(
             .map(|s| s.string().to_string())
             .unwrap_or_default(),
         overrides: vec![],
+        required_overrides: if c.flags.contains(hir::ClassDefinitionFlags::ABSTRACT) {
+            required_overrides(hir, c, files, item_provider)
+        } else {
+            vec![]
+        },
         public: VariablesAndFunctions::default(),
         protected: VariablesAndFunctions::default(),
         private: VariablesAndFunctions::default(),
@@ -772,6 +995,14 @@ fn class_doc(
                         files,
                     ),
                     overrides,
+                    overridden_by: if f
+                        .flags
+                        .intersects(hir::FunctionFlags::VIRTUAL | hir::FunctionFlags::ABSTRACT)
+                    {
+                        overridden_by(subclasses, c, f, files, item_provider)
+                    } else {
+                        vec![]
+                    },
                     deprecated: f.deprecated.as_ref().map(transform_deprecated),
                 };
                 if f.flags.contains(hir::FunctionFlags::OVERRIDE) {
@@ -834,6 +1065,7 @@ fn class_doc(
                     &class_to_add.context,
                     e,
                     files,
+                    item_provider,
                 );
                 class_to_add.inner_enums.push(enum_to_add);
             }
@@ -851,6 +1083,12 @@ fn class_doc(
                         .unwrap_or_else(|| "".to_string()),
                     span: co.span,
                     def: reconstruct_constant_declaration(owner, co, item_provider, files),
+                    value: evaluated_value(
+                        files.text_from_span(co.expr.span.unwrap()),
+                        item_provider,
+                        &class_to_add.context,
+                    ),
+                    elements: vec![],
                 };
                 class_to_add.constants.push(const_to_add);
             }
@@ -874,6 +1112,13 @@ fn class_doc(
                         &class_to_add.context,
                         files,
                     ),
+                    value: None,
+                    elements: static_const_array_elements(
+                        sca,
+                        item_provider,
+                        &class_to_add.context,
+                        files,
+                    ),
                 };
                 class_to_add.constants.push(const_to_add);
             }
@@ -1045,6 +1290,7 @@ fn struct_doc(
                         files,
                     ),
                     overrides: None,
+                    overridden_by: vec![],
                     deprecated: f.deprecated.as_ref().map(transform_deprecated),
                 };
                 if f.flags.contains(hir::FunctionFlags::PROTECTED) {
@@ -1091,6 +1337,7 @@ fn struct_doc(
                     &struct_to_add.context,
                     e,
                     files,
+                    item_provider,
                 );
                 struct_to_add.inner_enums.push(enum_to_add);
             }
@@ -1108,6 +1355,12 @@ fn struct_doc(
                         .unwrap_or_else(|| "".to_string()),
                     span: c.span,
                     def: reconstruct_constant_declaration(owner, c, item_provider, files),
+                    value: evaluated_value(
+                        files.text_from_span(c.expr.span.unwrap()),
+                        item_provider,
+                        &struct_to_add.context,
+                    ),
+                    elements: vec![],
                 };
                 struct_to_add.constants.push(const_to_add);
             }
@@ -1131,6 +1384,13 @@ fn struct_doc(
                         &struct_to_add.context,
                         files,
                     ),
+                    value: None,
+                    elements: static_const_array_elements(
+                        sca,
+                        item_provider,
+                        &struct_to_add.context,
+                        files,
+                    ),
                 };
                 struct_to_add.constants.push(const_to_add);
             }
@@ -1152,12 +1412,13 @@ fn struct_doc(
     struct_to_add
 }
 
-fn enum_doc(
+pub fn enum_doc(
     name: &str,
     no_context_name: &str,
     parent_context: &[NameSymbol],
     e: &ir_common::EnumDefinition,
     files: &Files,
+    item_provider: &ItemProvider,
 ) -> Enum {
     let mut enum_to_add = Enum {
         context: parent_context.to_vec(),
@@ -1169,12 +1430,34 @@ fn enum_doc(
             .map(|s| s.string().to_string())
             .unwrap_or_default(),
         enumerators: vec![],
+        is_flags: false,
     };
+    // hidden enumerators still count towards the values of the ones after them
+    let mut values: Vec<(&str, Option<i64>)> = vec![];
     for i in e.variants.iter() {
+        let inner_name = files.text_from_span(i.name.span);
+        let value = match &i.init {
+            Some(init) => {
+                crate::consteval::eval_int(files.text_from_span(init.span.unwrap()), |n| {
+                    match values.iter().find(|(name, _)| name.eq_ignore_ascii_case(n)) {
+                        Some((_, v)) => *v,
+                        // anything else, like a constant or another enum's enumerator
+                        None => match item_provider.eval_constant(n, parent_context)? {
+                            crate::consteval::Value::Int(i) => Some(i),
+                            crate::consteval::Value::Float(_) => None,
+                        },
+                    }
+                })
+            }
+            None => match values.last() {
+                Some((_, v)) => v.and_then(|v| v.checked_add(1)),
+                None => Some(0),
+            },
+        };
+        values.push((inner_name, value));
         if should_skip(i.doc_comment.as_ref()) {
             continue;
         }
-        let inner_name = files.text_from_span(i.name.span);
         let enumerator_to_add = Enumerator {
             context: enum_to_add.context.clone(),
             name: inner_name.to_string(),
@@ -1184,9 +1467,26 @@ fn enum_doc(
                 .map(|s| s.string().to_string())
                 .unwrap_or_else(|| "".to_string()),
             decl: reconstruct_enumerator_declaration(Owner::Enum(vec![name.to_string()]), i, files),
+            value,
         };
         enum_to_add.enumerators.push(enumerator_to_add);
     }
+    // implicit values count up from 0 and would look like flags up to 2, so at least one value
+    // has to be given explicitly. Values wrap like a 32-bit `int`, so the top bit is negative.
+    let flags = values
+        .iter()
+        .filter(|(_, v)| *v != Some(0))
+        .map(|(_, v)| v.map(|v| v as u32).filter(|v| v.is_power_of_two()))
+        .collect::<Option<Vec<_>>>();
+    enum_to_add.is_flags = match flags {
+        // each flag needs a bit of its own
+        Some(flags) => {
+            flags.len() >= 2
+                && e.variants.iter().any(|v| v.init.is_some())
+                && flags.iter().fold(0, |acc, f| acc | f).count_ones() as usize == flags.len()
+        }
+        None => false,
+    };
     enum_to_add
 }
 
@@ -1196,7 +1496,7 @@ pub fn hir_to_doc_structures(
     hir: &hir::TopLevel,
     files: &Files,
     item_provider: &ItemProvider,
-    dependencies: &Dependencies,
+    archive_num: usize,
     builtins: Vec<Builtin>,
     document_globals: bool,
 ) -> Documentation {
@@ -1210,8 +1510,9 @@ pub fn hir_to_doc_structures(
         globals: None,
         summary_doc,
     };
+    let subclasses = subclass_map(hir);
     for (_, node) in hir.definitions.iter() {
-        if node[0].archive_num != dependencies.get_final_archive_num() {
+        if node[0].archive_num != archive_num {
             continue;
         }
         let name = files.text_from_span(node[0].name().span);
@@ -1220,7 +1521,7 @@ pub fn hir_to_doc_structures(
                 if should_skip(c.doc_comment.as_ref()) {
                     continue;
                 }
-                let class_to_add = class_doc(name, &[], hir, c, files, item_provider);
+                let class_to_add = class_doc(name, &[], hir, &subclasses, c, files, item_provider);
                 docs.classes.push(class_to_add);
             }
             hir::TopLevelDefinitionKind::Struct(s) => {
@@ -1240,7 +1541,7 @@ pub fn hir_to_doc_structures(
                 if should_skip(e.doc_comment.as_ref()) {
                     continue;
                 }
-                let enum_to_add = enum_doc(name, name, &[], e, files);
+                let enum_to_add = enum_doc(name, name, &[], e, files, item_provider);
                 docs.enums.push(enum_to_add);
             }
             hir::TopLevelDefinitionKind::Const(c) => {
@@ -1257,6 +1558,12 @@ pub fn hir_to_doc_structures(
                         .unwrap_or_else(|| "".to_string()),
                     span: c.span,
                     def: reconstruct_constant_declaration(owner, c, item_provider, files),
+                    value: evaluated_value(
+                        files.text_from_span(c.expr.span.unwrap()),
+                        item_provider,
+                        &[],
+                    ),
+                    elements: vec![],
                 };
                 docs.constants.push(const_to_add);
             }
@@ -1,33 +1,61 @@
-use std::{
-    io::Write,
-    path::{Path, PathBuf},
-};
+use std::{io::Write, path::PathBuf};
 
 use anyhow::Context;
 use crossterm::{
     terminal::{Clear, ClearType},
     ExecutableCommand,
 };
-use directories::ProjectDirs;
 use git2::{FetchOptions, RemoteCallbacks, Repository};
 use sha2::{Digest, Sha256};
 
-fn change_head_in<P: AsRef<Path>>(url: &str, path: P, refname: &str) -> anyhow::Result<()> {
-    use std::io::IsTerminal;
+/// What a git dependency is pinned to.
+#[derive(Debug, Clone)]
+pub enum GitRef {
+    Branch(String),
+    Tag(String),
+    /// A commit hash, which may be abbreviated.
+    Rev(String),
+}
 
-    let repo = Repository::open(path)?;
+impl GitRef {
+    /// Picks the reference from a dependency's `refname`, `tag` and `rev` options, exactly one of
+    /// which must be given.
+    pub fn from_options(
+        refname: Option<&str>,
+        tag: Option<&str>,
+        rev: Option<&str>,
+    ) -> anyhow::Result<Self> {
+        match (refname, tag, rev) {
+            (Some(b), None, None) => Ok(Self::Branch(b.to_string())),
+            (None, Some(t), None) => Ok(Self::Tag(t.to_string())),
+            (None, None, Some(r)) => Ok(Self::Rev(r.to_string())),
+            _ => anyhow::bail!("git dependencies need exactly one of `refname`, `tag` or `rev`"),
+        }
+    }
 
-    repo.remote_set_url("origin", url)
-        .context("failed to set url")?;
-    repo.remote_add_fetch(
-        "origin",
-        &format!("+refs/heads/{refname}:refs/remotes/origin/{refname}"),
-    )
-    .context("failed to add remote")?;
+    /// How the reference is written in the lockfile.
+    fn lock_key(&self) -> String {
+        match self {
+            Self::Branch(b) => format!("branch:{b}"),
+            Self::Tag(t) => format!("tag:{t}"),
+            Self::Rev(r) => format!("rev:{r}"),
+        }
+    }
+}
+
+impl std::fmt::Display for GitRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Branch(b) => write!(f, "branch {b}"),
+            Self::Tag(t) => write!(f, "tag {t}"),
+            Self::Rev(r) => write!(f, "commit {r}"),
+        }
+    }
+}
+
+fn fetch_options<'a>(shallow: bool) -> FetchOptions<'a> {
+    use std::io::IsTerminal;
 
-    let mut remote = repo
-        .find_remote("origin")
-        .context("failed to find remote")?;
     let mut cb = RemoteCallbacks::new();
 
     let mut cleared = false;
@@ -62,64 +90,213 @@ fn change_head_in<P: AsRef<Path>>(url: &str, path: P, refname: &str) -> anyhow::
     });
 
     let mut fo = FetchOptions::new();
-    fo.depth(1);
+    if shallow {
+        fo.depth(1);
+    }
     fo.remote_callbacks(cb);
-    remote
-        .fetch(&[refname] as &[&str], Some(&mut fo), None)
-        .context("failed to fetch")?;
+    fo
+}
+
+/// The revision `reference` can be found at once it has been fetched.
+fn revspec(reference: &GitRef) -> String {
+    match reference {
+        GitRef::Branch(b) => format!("origin/{b}"),
+        GitRef::Tag(t) => format!("refs/tags/{t}"),
+        GitRef::Rev(r) => r.clone(),
+    }
+}
+
+/// Fetches `reference` from `url` into `repo`.
+fn fetch(repo: &Repository, url: &str, reference: &GitRef) -> anyhow::Result<()> {
+    repo.remote_set_url("origin", url)
+        .context("failed to set url")?;
+
+    let mut remote = repo
+        .find_remote("origin")
+        .context("failed to find remote")?;
+
+    let refspec = match reference {
+        GitRef::Branch(b) => format!("+refs/heads/{b}:refs/remotes/origin/{b}"),
+        GitRef::Tag(t) => format!("+refs/tags/{t}:refs/tags/{t}"),
+        GitRef::Rev(r) => r.clone(),
+    };
+    let fetched = remote.fetch(&[refspec.as_str()], Some(&mut fetch_options(true)), None);
+    match (fetched, reference) {
+        (Ok(()), _) => {}
+        // servers only let single commits be fetched by their full hash, if at all, so fall back
+        // to fetching everything
+        (Err(_), GitRef::Rev(_)) => remote
+            .fetch(
+                &[
+                    "+refs/heads/*:refs/remotes/origin/*",
+                    "+refs/tags/*:refs/tags/*",
+                ],
+                Some(&mut fetch_options(false)),
+                None,
+            )
+            .context("failed to fetch")?,
+        (Err(e), _) => return Err(e).context("failed to fetch"),
+    }
     std::io::stderr()
         .execute(Clear(ClearType::CurrentLine))
         .unwrap();
+    Ok(())
+}
 
-    let (object, reference) = repo
-        .revparse_ext(&format!("origin/{refname}"))
-        .context("failed to parse refname")?;
+/// Checks out `reference`, which must already have been fetched, returning the hash of the
+/// commit.
+fn checkout(repo: &Repository, reference: &GitRef) -> anyhow::Result<String> {
+    let commit = repo
+        .revparse_single(&revspec(reference))
+        .context(format!("failed to find {}", reference))?
+        .peel_to_commit()
+        .context(format!("{} isn't a commit", reference))?;
 
-    repo.checkout_tree(&object, None)
+    repo.checkout_tree(commit.as_object(), None)
         .context("failed to check out")?;
-    repo.reset(&object, git2::ResetType::Hard, None)
+    repo.reset(commit.as_object(), git2::ResetType::Hard, None)
         .context("failed to reset")?;
+    repo.set_head_detached(commit.id())
+        .context("failed to set head")?;
 
-    match reference {
-        Some(gref) => repo.set_head(gref.name().unwrap()),
-        None => repo.set_head_detached(object.id()),
+    Ok(commit.id().to_string())
+}
+
+/// `docs/zscdoc.lock`, which records the commit each git dependency resolved to so that later
+/// builds use the same code.
+#[derive(serde::Serialize, serde::Deserialize, Default)]
+pub struct Lockfile {
+    #[serde(default, rename = "git")]
+    entries: Vec<LockedGit>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct LockedGit {
+    url: String,
+    #[serde(rename = "ref")]
+    reference: String,
+    commit: String,
+}
+
+impl Lockfile {
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
     }
-    .context("failed to set head")?;
 
-    Ok(())
+    fn get(&self, url: &str, reference: &GitRef) -> Option<String> {
+        let key = reference.lock_key();
+        self.entries
+            .iter()
+            .find(|e| e.url == url && e.reference == key)
+            .map(|e| e.commit.clone())
+    }
+
+    fn set(&mut self, url: &str, reference: &GitRef, commit: &str) {
+        let key = reference.lock_key();
+        self.entries
+            .retain(|e| !(e.url == url && e.reference == key));
+        self.entries.push(LockedGit {
+            url: url.to_string(),
+            reference: key,
+            commit: commit.to_string(),
+        });
+    }
 }
 
-pub fn clone_git(repo: &str, refname: &str) -> anyhow::Result<PathBuf> {
-    if let Some(proj_dirs) = ProjectDirs::from("", "zscdoc", "zscdoc") {
-        let cache_dir = proj_dirs.cache_dir();
-        let r = fs_extra::dir::create_all(cache_dir, false);
-        match &r {
-            Ok(()) => {}
-            Err(e) => match e.kind {
-                fs_extra::error::ErrorKind::AlreadyExists => {}
-                _ => {
-                    r.context("failed to make cache directory")?;
-                }
-            },
-        };
-        let mut repo_name_hasher = Sha256::new();
-        repo_name_hasher.update(repo.as_bytes());
-        let repo_hash = repo_name_hasher.finalize();
-        let path = cache_dir.join("checkouts").join(format!("{:x}", repo_hash));
-        if path.exists() {
-            match change_head_in(repo, &path, refname) {
-                Ok(()) => {
-                    return Ok(path);
-                }
-                Err(_) => {
-                    std::fs::remove_dir_all(&path)?;
-                }
-            };
+/// Clones git dependencies at the commits recorded in a lockfile, and records the commits they
+/// end up at in a new one.
+pub struct Locker {
+    previous: Lockfile,
+    current: Lockfile,
+    update: bool,
+    offline: bool,
+}
+
+impl Locker {
+    /// With `update` set, dependencies move to the latest commit of their branch or tag instead
+    /// of the locked one. With `offline` set, nothing is fetched and the checkouts already in the
+    /// cache are used.
+    pub fn new(previous: Lockfile, update: bool, offline: bool) -> Self {
+        Self {
+            previous,
+            current: Lockfile::default(),
+            update,
+            offline,
         }
-        Repository::init(&path)?;
-        change_head_in(repo, &path, refname)?;
+    }
+
+    pub fn clone_git(&mut self, url: &str, reference: &GitRef) -> anyhow::Result<PathBuf> {
+        let locked = if self.update {
+            None
+        } else {
+            self.previous.get(url, reference)
+        };
+        let (path, commit) = clone_git(url, reference, locked.as_deref(), self.offline)?;
+        self.current.set(url, reference, &commit);
         Ok(path)
-    } else {
-        anyhow::bail!("Couldn't get a cache directory");
     }
+
+    pub fn finish(self) -> Lockfile {
+        self.current
+    }
+}
+
+/// The folder git dependencies with the URL `url` are checked out in.
+pub fn checkout_dir(url: &str) -> anyhow::Result<PathBuf> {
+    let mut repo_name_hasher = Sha256::new();
+    repo_name_hasher.update(url.as_bytes());
+    let repo_hash = repo_name_hasher.finalize();
+    Ok(crate::cache::cache_dir()?
+        .join("checkouts")
+        .join(format!("{:x}", repo_hash)))
+}
+
+/// Checks out `reference` of the repository at `repo`, or the commit `locked` if given, returning
+/// the path of the checkout and the commit it's at. When `offline` is set, only what's already in
+/// the cache is used.
+fn clone_git(
+    repo: &str,
+    reference: &GitRef,
+    locked: Option<&str>,
+    offline: bool,
+) -> anyhow::Result<(PathBuf, String)> {
+    let target = match locked {
+        Some(commit) => GitRef::Rev(commit.to_string()),
+        None => reference.clone(),
+    };
+    let path = checkout_dir(repo)?;
+
+    if offline {
+        if !path.exists() {
+            anyhow::bail!(
+                "{} isn't in the cache, so it can't be used with `--offline-deps`",
+                repo
+            );
+        }
+        let cached = Repository::open(&path).context("the cached checkout is broken")?;
+        let commit = checkout(&cached, &target)
+            .context(format!("{} of {} isn't in the cache", target, repo))?;
+        return Ok((path, commit));
+    }
+
+    // only a checkout git can't make sense of is thrown away - a failed fetch leaves the cache
+    // alone so that it can still be built from offline
+    let cached = match Repository::open(&path) {
+        Ok(r) => r,
+        Err(_) => {
+            if path.exists() {
+                std::fs::remove_dir_all(&path)?;
+            }
+            Repository::init(&path)?
+        }
+    };
+    // a locked commit that's already been fetched doesn't need the network
+    if locked.is_some() {
+        if let Ok(commit) = checkout(&cached, &target) {
+            return Ok((path, commit));
+        }
+    }
+    fetch(&cached, repo, &target)?;
+    let commit = checkout(&cached, &target)?;
+    Ok((path, commit))
 }
@@ -0,0 +1,160 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use zscript_parser::filesystem::GZDoomFolderFileSystem;
+
+/// How deep into the archive to look for an include file.
+const MAX_SEARCH_DEPTH: usize = 3;
+
+fn is_zscript_file(path: &Path) -> bool {
+    matches!(
+        path.extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase())
+            .as_deref(),
+        Some("zs" | "zsc" | "zc" | "txt")
+    )
+}
+
+/// Finds files in `folder` that are likely to include everything else, i.e. `include.zs` and
+/// similar, shallowest first.
+fn find_include_files(folder: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let mut found = vec![];
+    let mut to_visit = vec![(folder.to_path_buf(), 0)];
+    while let Some((dir, depth)) = to_visit.pop() {
+        for e in std::fs::read_dir(&dir).context(format!("couldn't read {:?}", dir))? {
+            let path = e?.path();
+            if path.is_dir() {
+                if depth < MAX_SEARCH_DEPTH && !path.ends_with("docs") {
+                    to_visit.push((path, depth + 1));
+                }
+            } else if is_zscript_file(&path)
+                && path
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .map(|s| s.eq_ignore_ascii_case("include"))
+                    .unwrap_or(false)
+            {
+                found.push(path);
+            }
+        }
+    }
+    found.sort_by_key(|p| p.components().count());
+    Ok(found)
+}
+
+/// The name GZDoom gives the file at `path`, i.e. its path in the archive without the extension.
+fn lump_name(folder: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(folder).ok()?.with_extension("");
+    Some(relative.to_str()?.replace('\\', "/").to_lowercase())
+}
+
+/// Looks for a `gzdoom.pk3` next to `folder`, which most libraries will want as a dependency.
+fn find_sibling_gzdoom_pk3(folder: &Path) -> Option<String> {
+    let parent = std::fs::canonicalize(folder).ok()?.parent()?.to_path_buf();
+    std::fs::read_dir(parent)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.file_name().to_str().map(|n| n.to_string()))
+        .find(|n| n.eq_ignore_ascii_case("gzdoom.pk3") || n.eq_ignore_ascii_case("uzdoom.pk3"))
+}
+
+fn toml_string(s: &str) -> String {
+    toml::Value::String(s.to_string()).to_string()
+}
+
+/// Writes a starter `docs/zscdoc.toml` and `docs/summary.md` for the archive at `folder`.
+pub fn init(folder: &str, nice_name: Option<&str>) -> anyhow::Result<()> {
+    let folder_path = Path::new(folder);
+    if !folder_path.is_dir() {
+        anyhow::bail!("{:?} isn't a folder", folder);
+    }
+    let docs_path = folder_path.join("docs");
+    let config_path = docs_path.join("zscdoc.toml");
+    let summary_path = docs_path.join("summary.md");
+    if config_path.exists() {
+        anyhow::bail!("{:?} already exists", config_path);
+    }
+
+    let mut filesystem = GZDoomFolderFileSystem::new(folder.to_string(), folder.to_string())
+        .context("couldn't load a path")?;
+
+    // an archive with a root `zscript` lump is documented from there, while libraries meant to be
+    // copied into other projects usually have an include file in a folder named after them
+    let include = if filesystem.get_file("zscript").is_some() {
+        None
+    } else {
+        find_include_files(folder_path)?
+            .into_iter()
+            .filter_map(|p| Some((lump_name(folder_path, &p)?, p)))
+            .find(|(lump, _)| filesystem.get_file(lump).is_some())
+    };
+    let base_file = match &include {
+        Some((lump, _)) => lump.clone(),
+        None => {
+            if filesystem.get_file("zscript").is_none() {
+                eprintln!(
+                    "Warning: couldn't find a root `zscript` file or an include file, so \
+                     `base_file` will need setting by hand"
+                );
+            }
+            crate::base_file_default()
+        }
+    };
+
+    let nice_name = match nice_name {
+        Some(n) => n.to_string(),
+        None => include
+            .as_ref()
+            .and_then(|(_, p)| p.parent())
+            .filter(|p| *p != folder_path)
+            .unwrap_or(folder_path)
+            .canonicalize()
+            .ok()
+            .and_then(|p| {
+                p.file_stem()
+                    .and_then(|s| s.to_str())
+                    .map(|s| s.to_string())
+            })
+            .context("couldn't work out a name for the archive - pass `--name`")?,
+    };
+
+    let mut config = format!(
+        "[archive]\nnice_name = {}\nbase_file = {}\n",
+        toml_string(&nice_name),
+        toml_string(&base_file)
+    );
+    if let Some(pk3) = find_sibling_gzdoom_pk3(folder_path) {
+        eprintln!(
+            "Found {} next to the archive - uncomment its [[dependency]] in zscdoc.toml to link to it",
+            pk3
+        );
+        // left commented out, since links to it are broken until the url is filled in
+        config.push_str(&format!(
+            concat!(
+                "\n# uncomment once the url of {pk3}'s documentation is filled in\n",
+                "# [[dependency]]\n",
+                "# path = {path}\n",
+                "# url = \"\"\n",
+            ),
+            pk3 = pk3,
+            path = toml_string(&format!("../{}", pk3)),
+        ));
+    }
+
+    std::fs::create_dir_all(&docs_path).context("failed to make docs folder")?;
+    std::fs::write(&config_path, config).context("couldn't write zscdoc.toml")?;
+    eprintln!("Wrote {:?}", config_path);
+    if !summary_path.exists() {
+        std::fs::write(
+            &summary_path,
+            format!(
+                "# {}\n\nThis text is shown on the front page of the documentation.\n",
+                nice_name
+            ),
+        )
+        .context("couldn't write summary.md")?;
+        eprintln!("Wrote {:?}", summary_path);
+    }
+    Ok(())
+}
@@ -1,4 +1,6 @@
-use std::collections::HashMap;
+use std::{cell::RefCell, collections::HashMap};
+
+use itertools::Itertools;
 
 use zscript_parser::{
     filesystem::Files,
@@ -9,12 +11,26 @@ use zscript_parser::{
 
 use crate::{
     builtin::BuiltinTypeHir,
-    structures::{Dependencies, LinkedSection, LinkedSectionKind, Owner},
+    consteval::Value,
+    structures::{Dependencies, Inventory, InventoryItem, LinkedSection, LinkedSectionKind, Owner},
 };
 
+/// The source text of a constant's value, which is only evaluated when it's needed.
+#[derive(Debug)]
+struct ConstantExpr {
+    text: String,
+    /// The context the names in `text` are looked up from.
+    context: Vec<NameSymbol>,
+}
+
 #[derive(Debug)]
 pub struct ItemProvider {
     items: HashMap<Vec<NameSymbol>, LinkedSection>,
+    constants: HashMap<Vec<NameSymbol>, ConstantExpr>,
+    /// The values of constants that have been evaluated, so each is only evaluated once. A
+    /// constant that's still being evaluated is `None` here, which stops constants that refer to
+    /// themselves.
+    evaluated: RefCell<HashMap<Vec<NameSymbol>, Option<Value>>>,
 }
 
 impl ItemProvider {
@@ -54,6 +70,70 @@ impl ItemProvider {
         Some(resolved_chain)
     }
 
+    /// Evaluates the constant expression `text`, looking up any constants it refers to from
+    /// `context`.
+    pub fn eval_constant(&self, text: &str, context: &[NameSymbol]) -> Option<Value> {
+        crate::consteval::eval(text, |name| {
+            let chain = name.split('.').map(intern_name).collect_vec();
+            // like `resolve`, names are looked for in each enclosing context in turn
+            let (path, c) = (0..=context.len()).rev().find_map(|n| {
+                let mut path = context[..n].to_vec();
+                path.extend(chain.iter().copied());
+                self.constants.get_key_value(&path)
+            })?;
+            if let Some(v) = self.evaluated.borrow().get(path) {
+                return *v;
+            }
+            self.evaluated.borrow_mut().insert(path.clone(), None);
+            let v = self.eval_constant(&c.text, &c.context);
+            self.evaluated.borrow_mut().insert(path.clone(), v);
+            v
+        })
+    }
+
+    /// Lists the items of the documented archive, as opposed to those of its dependencies.
+    pub fn inventory(&self, name: &str) -> Inventory {
+        let mut items = self
+            .items
+            .iter()
+            .filter(|(_, i)| i.link_prefix.is_none())
+            .map(|(context, i)| {
+                // the prefixes of an item's context are usually items themselves, which have the
+                // name as written, but not always, such as the hidden struct globals live in
+                let path = (1..=context.len())
+                    .map(|n| match self.items.get(&context[..n]) {
+                        Some(i) => i.text.clone(),
+                        None => context[n - 1].string().to_string(),
+                    })
+                    .collect_vec();
+                InventoryItem {
+                    path,
+                    text: i.text.clone(),
+                    kind: i.kind.clone(),
+                }
+            })
+            .collect_vec();
+        items.sort_by(|a, b| a.path.cmp(&b.path));
+        Inventory {
+            name: name.to_string(),
+            items,
+        }
+    }
+
+    /// Adds the items of a dependency that was loaded from its `items.json` rather than parsed.
+    /// Items that were parsed take precedence.
+    pub fn add_inventory(&mut self, inventory: &Inventory, link_prefix: &str) {
+        for i in inventory.items.iter() {
+            self.items
+                .entry(i.path.iter().map(|p| intern_name(p)).collect())
+                .or_insert_with(|| LinkedSection {
+                    link_prefix: Some(link_prefix.to_string()),
+                    text: i.text.clone(),
+                    kind: i.kind.clone(),
+                });
+        }
+    }
+
     pub fn add_builtins<'i, OI, II>(
         &mut self,
         builtins: OI,
@@ -65,50 +145,94 @@ impl ItemProvider {
     {
         for (i, bs) in builtins.into_iter().enumerate() {
             for b in bs.into_iter() {
-                let context = vec![intern_name(&b.name)];
-                let name = b.name.clone();
-                let owner = Owner::Builtin(name.clone());
-                self.items.insert(
-                    context.clone(),
-                    LinkedSection {
-                        link_prefix: dependencies.get_link_prefix(i),
-                        text: name.clone(),
-                        kind: LinkedSectionKind::Builtin { link: name },
-                    },
-                );
-                for m in b.members.iter() {
-                    let context = context_with(&context, m.def.name.symbol);
-                    let name = files.text_from_span(m.def.name.span).to_string();
-                    self.items.insert(
-                        context.clone(),
-                        LinkedSection {
-                            link_prefix: dependencies.get_link_prefix(i),
-                            text: name.clone(),
-                            kind: LinkedSectionKind::Member {
-                                owner: owner.clone(),
-                                link: name,
-                            },
-                        },
-                    );
-                }
-                for f in b.functions.iter() {
-                    let context = context_with(&context, f.def.name.symbol);
-                    let name = files.text_from_span(f.def.name.span).to_string();
-                    self.items.insert(
-                        context.clone(),
-                        LinkedSection {
-                            link_prefix: dependencies.get_link_prefix(i),
-                            text: name.clone(),
-                            kind: LinkedSectionKind::Function {
-                                owner: owner.clone(),
-                                link: name,
-                            },
-                        },
-                    );
-                }
+                self.add_builtin(b, &[], None, files, dependencies, i);
             }
         }
     }
+
+    fn add_builtin(
+        &mut self,
+        b: &BuiltinTypeHir,
+        parent_context: &[NameSymbol],
+        parent_name: Option<&str>,
+        files: &Files,
+        dependencies: &Dependencies,
+        archive_num: usize,
+    ) {
+        let context = context_with(parent_context, intern_name(&b.name));
+        let name = match parent_name {
+            Some(p) => format!("{}.{}", p, b.name),
+            None => b.name.clone(),
+        };
+        let owner = Owner::Builtin(name.clone());
+        self.items.insert(
+            context.clone(),
+            LinkedSection {
+                link_prefix: dependencies.get_link_prefix(archive_num),
+                text: b.name.clone(),
+                kind: LinkedSectionKind::Builtin { link: name.clone() },
+            },
+        );
+        for m in b.members.iter() {
+            let context = context_with(&context, m.def.name.symbol);
+            let name = files.text_from_span(m.def.name.span).to_string();
+            self.items.insert(
+                context.clone(),
+                LinkedSection {
+                    link_prefix: dependencies.get_link_prefix(archive_num),
+                    text: name.clone(),
+                    kind: LinkedSectionKind::Member {
+                        owner: owner.clone(),
+                        link: name,
+                    },
+                },
+            );
+        }
+        for f in b.functions.iter() {
+            let context = context_with(&context, f.def.name.symbol);
+            let name = files.text_from_span(f.def.name.span).to_string();
+            self.items.insert(
+                context.clone(),
+                LinkedSection {
+                    link_prefix: dependencies.get_link_prefix(archive_num),
+                    text: name.clone(),
+                    kind: LinkedSectionKind::Function {
+                        owner: owner.clone(),
+                        link: name,
+                    },
+                },
+            );
+        }
+        for c in b.constants.iter() {
+            let const_context = context_with(&context, c.def.name.symbol);
+            let name = files.text_from_span(c.def.name.span).to_string();
+            self.constants.insert(
+                const_context.clone(),
+                ConstantExpr {
+                    text: files.text_from_span(c.def.expr.span.unwrap()).to_string(),
+                    context: context.clone(),
+                },
+            );
+            self.items.insert(
+                const_context,
+                LinkedSection {
+                    link_prefix: dependencies.get_link_prefix(archive_num),
+                    text: name.clone(),
+                    kind: LinkedSectionKind::Constant {
+                        owner: owner.clone(),
+                        link: name,
+                    },
+                },
+            );
+        }
+        for e in b.enums.iter() {
+            e.def
+                .add(&context, self, files, &owner, dependencies, archive_num);
+        }
+        for s in b.structs.iter() {
+            self.add_builtin(s, &context, Some(&name), files, dependencies, archive_num);
+        }
+    }
 }
 
 pub trait ToItemProvider {
@@ -118,6 +242,8 @@ impl ToItemProvider for TopLevel {
     fn to_item_provider(&self, files: &Files, dependencies: &Dependencies) -> ItemProvider {
         let mut ret = ItemProvider {
             items: HashMap::new(),
+            constants: HashMap::new(),
+            evaluated: RefCell::new(HashMap::new()),
         };
         self.add(&[], &mut ret, files, &Owner::Global, dependencies, 0);
         ret
@@ -450,8 +576,30 @@ impl AddToItemProvider for EnumDefinition {
                 kind: LinkedSectionKind::Enum { link },
             },
         );
+        // implicit values are stored as expressions too, so they're only evaluated if needed. They
+        // count from the last explicit value rather than the previous enumerator, so evaluating
+        // one doesn't mean evaluating every enumerator before it.
+        let mut last_explicit: Option<&str> = None;
+        let mut offset = 0;
         for v in self.variants.iter() {
             let name = files.text_from_span(v.name.span).to_string();
+            let text = match (&v.init, last_explicit) {
+                (Some(init), _) => {
+                    last_explicit = Some(files.text_from_span(v.name.span));
+                    offset = 0;
+                    files.text_from_span(init.span.unwrap()).to_string()
+                }
+                (None, Some(e)) => format!("{} + {}", e, offset),
+                (None, None) => offset.to_string(),
+            };
+            offset += 1;
+            item_provider.constants.insert(
+                context_with(context, v.name.symbol),
+                ConstantExpr {
+                    text,
+                    context: context.to_vec(),
+                },
+            );
             item_provider.items.insert(
                 context_with(context, v.name.symbol),
                 LinkedSection {
@@ -477,10 +625,17 @@ impl AddToItemProvider for ConstDefinition {
         dependencies: &Dependencies,
         archive_num: usize,
     ) {
-        let context = context_with(context, self.name.symbol);
+        let const_context = context_with(context, self.name.symbol);
         let name = files.text_from_span(self.name.span).to_string();
+        item_provider.constants.insert(
+            const_context.clone(),
+            ConstantExpr {
+                text: files.text_from_span(self.expr.span.unwrap()).to_string(),
+                context: context.to_vec(),
+            },
+        );
         item_provider.items.insert(
-            context,
+            const_context,
             LinkedSection {
                 link_prefix: dependencies.get_link_prefix(archive_num),
                 text: name.to_string(),
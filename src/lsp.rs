@@ -0,0 +1,453 @@
+use std::{
+    collections::HashMap,
+    io::{BufRead, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use itertools::Itertools;
+use serde_json::{json, Value};
+use zscript_parser::{
+    filesystem::Files,
+    interner::{intern_name, NameSymbol},
+    Span,
+};
+
+use crate::{
+    item::ItemProvider,
+    search::{collect_items, SearchResultKind},
+    show::{ItemIndex, ShownItem},
+    structures::{Documentation, Struct},
+};
+
+const MAX_WORKSPACE_SYMBOLS: usize = 256;
+
+fn read_message(input: &mut impl BufRead) -> anyhow::Result<Option<Value>> {
+    let mut length = None;
+    loop {
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some(l) = line.strip_prefix("Content-Length:") {
+            length = Some(
+                l.trim()
+                    .parse::<usize>()
+                    .context("invalid Content-Length")?,
+            );
+        }
+    }
+    let length = length.context("message without a Content-Length")?;
+    let mut buf = vec![0; length];
+    input.read_exact(&mut buf)?;
+    Ok(Some(
+        serde_json::from_slice(&buf).context("message wasn't valid JSON")?,
+    ))
+}
+
+fn write_message(output: &mut impl Write, message: &Value) -> anyhow::Result<()> {
+    let text = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{}", text.len(), text)?;
+    output.flush()?;
+    Ok(())
+}
+
+fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let encoded = uri.strip_prefix("file://")?;
+    let raw = encoded.as_bytes();
+    let mut bytes = vec![];
+    let mut i = 0;
+    while i < raw.len() {
+        if raw[i] == b'%' {
+            if let Some(b) = encoded
+                .get(i + 1..i + 3)
+                .and_then(|h| u8::from_str_radix(h, 16).ok())
+            {
+                bytes.push(b);
+                i += 3;
+                continue;
+            }
+        }
+        bytes.push(raw[i]);
+        i += 1;
+    }
+    let path = String::from_utf8(bytes).ok()?;
+    // windows paths come through as `/C:/...`
+    let path = match path.strip_prefix('/') {
+        Some(p) if p.get(1..2) == Some(":") => p.to_string(),
+        _ => path,
+    };
+    Some(PathBuf::from(path))
+}
+
+fn path_to_uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut uri = "file://".to_string();
+    if !path.starts_with('/') {
+        uri.push('/');
+    }
+    for b in path.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' | b':' => {
+                uri.push(b as char)
+            }
+            _ => uri += &format!("%{:02X}", b),
+        }
+    }
+    uri
+}
+
+/// Converts an LSP position, whose `character` counts UTF-16 code units, to a byte offset.
+fn offset_of(text: &str, line: usize, character: usize) -> Option<usize> {
+    let line_start = if line == 0 {
+        0
+    } else {
+        text.match_indices('\n').nth(line - 1)?.0 + 1
+    };
+    let mut units = 0;
+    for (i, c) in text[line_start..].char_indices() {
+        if units >= character || c == '\n' {
+            return Some(line_start + i);
+        }
+        units += c.len_utf16();
+    }
+    Some(text.len())
+}
+
+fn position_of(text: &str, offset: usize) -> Value {
+    let before = &text[..offset.min(text.len())];
+    let line = before.matches('\n').count();
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let character: usize = before[line_start..].chars().map(char::len_utf16).sum();
+    json!({ "line": line, "character": character })
+}
+
+fn symbol_kind(kind: SearchResultKind) -> u32 {
+    // these are the numbers of the `SymbolKind` enumeration in the LSP specification
+    match kind {
+        SearchResultKind::Page => 1,
+        SearchResultKind::Class | SearchResultKind::Builtin => 5,
+        SearchResultKind::Function => 6,
+        SearchResultKind::Property | SearchResultKind::Flag => 7,
+        SearchResultKind::Member => 8,
+        SearchResultKind::Enum => 10,
+        SearchResultKind::Global => 13,
+        SearchResultKind::Constant => 14,
+        SearchResultKind::Enumerator => 22,
+        SearchResultKind::Struct => 23,
+    }
+}
+
+fn hover_markdown(item: &ShownItem) -> String {
+    let mut value = format!("```zscript\n{}\n```", item.signature);
+    if let Some(d) = item.deprecated {
+        value += &format!("\n\n**Deprecated since {}**", d.version);
+        if !d.reason.is_empty() {
+            value += &format!(": {}", d.reason);
+        }
+    }
+    if !item.doc_comment.trim().is_empty() {
+        value += "\n\n---\n\n";
+        value += &textwrap::dedent(item.doc_comment);
+    }
+    value
+}
+
+struct WorkspaceSymbol {
+    name: String,
+    container: String,
+    kind: SearchResultKind,
+    location: Value,
+}
+
+/// The scope of a class or struct, used to resolve names written inside it.
+struct Scope<'a> {
+    start: usize,
+    end: usize,
+    context: &'a [NameSymbol],
+}
+
+fn struct_spans(structs: &[Struct]) -> impl Iterator<Item = (Span, &[NameSymbol])> {
+    structs.iter().map(|s| (s.span, &s.context[..]))
+}
+
+struct Server<'a> {
+    item_provider: &'a ItemProvider,
+    files: &'a Files,
+    items: ItemIndex<'a>,
+    /// The folders of the documented archive and its dependencies, which the filenames of `files`
+    /// are relative to.
+    roots: Vec<PathBuf>,
+    scopes: HashMap<PathBuf, Vec<Scope<'a>>>,
+    symbols: Vec<WorkspaceSymbol>,
+    /// The text of documents open in the editor, which may differ from what's on disk.
+    open_documents: HashMap<String, String>,
+}
+
+impl<'a> Server<'a> {
+    fn new(
+        docs: &[(Option<&'a str>, &'a Documentation)],
+        item_provider: &'a ItemProvider,
+        files: &'a Files,
+        roots: Vec<PathBuf>,
+    ) -> Self {
+        let mut server = Self {
+            item_provider,
+            files,
+            items: ItemIndex::new(docs.iter().copied()),
+            roots,
+            scopes: HashMap::new(),
+            symbols: vec![],
+            open_documents: HashMap::new(),
+        };
+
+        let mut spans = vec![];
+        for (_, d) in docs.iter().copied() {
+            for c in d.classes.iter() {
+                spans.push((c.span, &c.context[..]));
+                spans.extend(struct_spans(&c.inner_structs));
+            }
+            spans.extend(struct_spans(&d.structs));
+        }
+        for (span, context) in spans {
+            if let Some(path) = server.span_path(span) {
+                server.scopes.entry(path).or_default().push(Scope {
+                    start: span.get_start(),
+                    end: span.get_end(),
+                    context,
+                });
+            }
+        }
+
+        server.symbols = docs
+            .iter()
+            .copied()
+            .flat_map(|(link_prefix, d)| {
+                collect_items(d).into_iter().map(move |i| (link_prefix, i))
+            })
+            .filter_map(|(link_prefix, i)| {
+                let item = server.items.find(link_prefix, &i.link)?;
+                let location = server.location(item.span?)?;
+                Some(WorkspaceSymbol {
+                    name: i.name,
+                    container: i.name_prelude.trim_end_matches('.').to_string(),
+                    kind: i.kind,
+                    location,
+                })
+            })
+            .collect_vec();
+
+        server
+    }
+
+    fn span_path(&self, span: Span) -> Option<PathBuf> {
+        let filename = self.files[span.get_file()].filename();
+        self.roots
+            .iter()
+            .map(|r| r.join(filename))
+            .find(|p| p.exists())
+            .and_then(|p| p.canonicalize().ok())
+    }
+
+    fn location(&self, span: Span) -> Option<Value> {
+        let path = self.span_path(span)?;
+        let text = self.files[span.get_file()].text();
+        Some(json!({
+            "uri": path_to_uri(&path),
+            "range": {
+                "start": position_of(text, span.get_start()),
+                "end": position_of(text, span.get_end()),
+            },
+        }))
+    }
+
+    /// The file, its current text and the byte offset of a `TextDocumentPositionParams`.
+    fn position(&self, params: &Value) -> Option<(PathBuf, String, usize)> {
+        let uri = params["textDocument"]["uri"].as_str()?;
+        let path = uri_to_path(uri)?;
+        let path = path.canonicalize().unwrap_or(path);
+        let text = match self.open_documents.get(uri) {
+            Some(t) => t.clone(),
+            None => std::fs::read_to_string(&path).ok()?,
+        };
+        let line = params["position"]["line"].as_u64()? as usize;
+        let character = params["position"]["character"].as_u64()? as usize;
+        let offset = offset_of(&text, line, character)?;
+        Some((path, text, offset))
+    }
+
+    /// The context of the innermost class or struct around `offset`.
+    fn context_at(&self, path: &Path, offset: usize) -> Vec<NameSymbol> {
+        self.scopes
+            .get(path)
+            .and_then(|scopes| {
+                scopes
+                    .iter()
+                    .filter(|s| s.start <= offset && offset <= s.end)
+                    .min_by_key(|s| s.end - s.start)
+            })
+            .map(|s| s.context.to_vec())
+            .unwrap_or_default()
+    }
+
+    /// The documented item named by the expression under the cursor.
+    fn item_at(&self, path: &Path, text: &str, offset: usize) -> Option<ShownItem<'a>> {
+        let bytes = text.as_bytes();
+        let is_ident = |b: u8| b.is_ascii_alphanumeric() || b == b'_';
+        let mut end = offset;
+        while end < bytes.len() && is_ident(bytes[end]) {
+            end += 1;
+        }
+        let mut start = offset;
+        while start > 0 && (is_ident(bytes[start - 1]) || bytes[start - 1] == b'.') {
+            start -= 1;
+        }
+        let parts = text[start..end]
+            .split('.')
+            .filter(|p| !p.is_empty())
+            .collect_vec();
+        let context = self.context_at(path, offset);
+        // the start of the expression may well be a variable rather than a type, so fall back to
+        // resolving shorter and shorter ends of it
+        (0..parts.len()).find_map(|i| {
+            let chain = parts[i..].iter().map(|p| intern_name(p));
+            let linked = self
+                .item_provider
+                .resolve(&context, chain)?
+                .last()
+                .copied()?;
+            self.items
+                .find(linked.link_prefix.as_deref(), &linked.kind.get_kind_href())
+        })
+    }
+
+    fn hover(&self, params: &Value) -> Option<Value> {
+        let (path, text, offset) = self.position(params)?;
+        let item = self.item_at(&path, &text, offset)?;
+        Some(json!({
+            "contents": { "kind": "markdown", "value": hover_markdown(&item) },
+        }))
+    }
+
+    fn definition(&self, params: &Value) -> Option<Value> {
+        let (path, text, offset) = self.position(params)?;
+        let item = self.item_at(&path, &text, offset)?;
+        self.location(item.span?)
+    }
+
+    fn workspace_symbols(&self, params: &Value) -> Value {
+        let query = params["query"].as_str().unwrap_or_default().to_lowercase();
+        self.symbols
+            .iter()
+            .filter(|s| s.name.to_lowercase().contains(&query))
+            .take(MAX_WORKSPACE_SYMBOLS)
+            .map(|s| {
+                json!({
+                    "name": s.name,
+                    "kind": symbol_kind(s.kind),
+                    "containerName": s.container,
+                    "location": s.location,
+                })
+            })
+            .collect()
+    }
+
+    /// Handles a request, returning `None` for methods that aren't supported.
+    fn request(&self, method: &str, params: &Value) -> Option<Value> {
+        match method {
+            "initialize" => Some(json!({
+                "capabilities": {
+                    // full document sync
+                    "textDocumentSync": 1,
+                    "hoverProvider": true,
+                    "definitionProvider": true,
+                    "workspaceSymbolProvider": true,
+                },
+                "serverInfo": { "name": "zscdoc", "version": env!("CARGO_PKG_VERSION") },
+            })),
+            "shutdown" => Some(Value::Null),
+            "textDocument/hover" => Some(self.hover(params).unwrap_or(Value::Null)),
+            "textDocument/definition" => Some(self.definition(params).unwrap_or(Value::Null)),
+            "workspace/symbol" => Some(self.workspace_symbols(params)),
+            _ => None,
+        }
+    }
+
+    fn notification(&mut self, method: &str, params: &Value) {
+        let uri = params["textDocument"]["uri"]
+            .as_str()
+            .map(|s| s.to_string());
+        match (method, uri) {
+            ("textDocument/didOpen", Some(uri)) => {
+                if let Some(text) = params["textDocument"]["text"].as_str() {
+                    self.open_documents.insert(uri, text.to_string());
+                }
+            }
+            ("textDocument/didChange", Some(uri)) => {
+                let text = params["contentChanges"]
+                    .as_array()
+                    .and_then(|c| c.last())
+                    .and_then(|c| c["text"].as_str());
+                if let Some(text) = text {
+                    self.open_documents.insert(uri, text.to_string());
+                }
+            }
+            ("textDocument/didClose", Some(uri)) => {
+                self.open_documents.remove(&uri);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Runs a language server over stdin and stdout until the client exits it.
+///
+/// The archive is only parsed once when the server starts, so hovers and definitions reflect the
+/// code as it was then, although the text being edited is still used to find what's under the
+/// cursor.
+pub fn run(
+    docs: &[(Option<&str>, &Documentation)],
+    item_provider: &ItemProvider,
+    files: &Files,
+    roots: Vec<PathBuf>,
+) -> anyhow::Result<()> {
+    let mut server = Server::new(docs, item_provider, files, roots);
+    eprintln!("Language server ready.");
+
+    let stdin = std::io::stdin();
+    let mut input = stdin.lock();
+    let mut output = std::io::stdout();
+    let mut shut_down = false;
+    while let Some(message) = read_message(&mut input)? {
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+        let Some(id) = message.get("id") else {
+            if method == "exit" {
+                if !shut_down {
+                    anyhow::bail!("language client exited without shutting down");
+                }
+                return Ok(());
+            }
+            server.notification(method, params);
+            continue;
+        };
+        if method == "shutdown" {
+            shut_down = true;
+        }
+        let response = match server.request(method, params) {
+            Some(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            None => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                // MethodNotFound
+                "error": { "code": -32601, "message": format!("unsupported method {:?}", method) },
+            }),
+        };
+        write_message(&mut output, &response)?;
+    }
+    Ok(())
+}
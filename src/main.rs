@@ -4,17 +4,30 @@
 mod item;
 mod structures;
 
+mod archive;
 mod builtin;
+mod cache;
+mod check_config;
 mod cli;
+mod consteval;
 mod coverage;
 mod document;
 mod git;
+mod init;
+mod lsp;
 mod render;
 mod search;
+mod show;
+mod sitemap;
+mod wiki;
 
 use crate::{
-    builtin::BuiltinTypeFromFile, cli::*, coverage::coverage_breakdown, item::ItemProvider,
-    render::render_from_markdown, structures::BaseUrl,
+    builtin::{BuiltinDeclarationFile, BuiltinSource, BuiltinTypeFromFile, BuiltinTypeHir},
+    cli::*,
+    coverage::coverage_breakdown,
+    item::ItemProvider,
+    render::render_from_markdown,
+    structures::{BaseUrl, BuiltinTheme, NavLink, SiteInfo, Templates},
 };
 use anyhow::Context as _;
 use clap::Parser;
@@ -38,13 +51,13 @@ struct Assets;
 #[folder = "web_stuff/dist"]
 struct Assets;
 
-#[derive(serde::Deserialize, Debug)]
+#[derive(serde::Deserialize, schemars::JsonSchema, Debug)]
 struct Config {
     archive: Archive,
     dependency: Option<Vec<Dependency>>,
 }
 
-#[derive(serde::Deserialize, Debug)]
+#[derive(serde::Deserialize, schemars::JsonSchema, Debug)]
 #[serde(untagged)]
 enum DependencyPathKind {
     Path {
@@ -52,13 +65,20 @@ enum DependencyPathKind {
     },
     Git {
         git: String,
-        refname: String,
+        /// A branch name.
+        refname: Option<String>,
+        tag: Option<String>,
+        rev: Option<String>,
         #[serde(default = "String::new")]
         base: String,
     },
+    /// The `items.json` written with another archive's documentation, or the folder containing it.
+    Inventory {
+        inventory: String,
+    },
 }
 
-#[derive(serde::Deserialize, Debug)]
+#[derive(serde::Deserialize, schemars::JsonSchema, Debug)]
 struct Dependency {
     #[serde(flatten)]
     find_at: DependencyPathKind,
@@ -68,7 +88,7 @@ struct Dependency {
 fn base_file_default() -> String {
     "zscript".to_string()
 }
-#[derive(serde::Deserialize, Debug)]
+#[derive(serde::Deserialize, schemars::JsonSchema, Debug)]
 struct Archive {
     nice_name: String,
     #[serde(default = "base_file_default")]
@@ -83,9 +103,26 @@ struct Archive {
     base_url: String,
     #[serde(default)]
     document_globals: bool,
+    #[serde(default)]
+    search_dependencies: bool,
+    site_image: Option<String>,
+    #[serde(default)]
+    theme: Theme,
+    #[serde(alias = "nav_link")]
+    nav_links: Option<Vec<NavLink>>,
+}
+
+#[derive(serde::Deserialize, schemars::JsonSchema, Debug, Default)]
+struct Theme {
+    #[serde(default)]
+    builtin: BuiltinTheme,
+    #[serde(alias = "stylesheet")]
+    stylesheets: Option<Vec<String>>,
+    #[serde(alias = "script")]
+    scripts: Option<Vec<String>>,
 }
 
-#[derive(serde::Deserialize, Debug)]
+#[derive(serde::Deserialize, schemars::JsonSchema, Debug)]
 struct MarkdownFile {
     filename: String,
     title: String,
@@ -97,7 +134,6 @@ pub struct VersionItem {
     nice_name: String,
     no_index: bool,
     title_suffix: String,
-    #[expect(unused)]
     latest: bool,
 }
 
@@ -109,7 +145,7 @@ pub struct VersionInfo {
     versions: Vec<VersionItem>,
 }
 
-#[derive(serde::Deserialize, Debug)]
+#[derive(serde::Deserialize, schemars::JsonSchema, Debug)]
 struct CopyFile {
     filename: String,
 }
@@ -128,6 +164,7 @@ struct CopyFileToRender {
 fn save_docs_to_folder(
     output: &str,
     docs: &structures::Documentation,
+    dependency_docs: &[structures::DependencyDocumentation],
     delete_without_confirm: bool,
     item_provider: &ItemProvider,
     favicon: Option<&[u8]>,
@@ -135,27 +172,13 @@ fn save_docs_to_folder(
     copy_files: &[CopyFileToRender],
     base: &BaseUrl,
     version_info: Option<VersionInfo>,
-    canonical_domain: Option<String>,
+    site: &SiteInfo,
+    compress_search_index: bool,
 ) -> anyhow::Result<()> {
     use std::fs::*;
     use std::io::*;
     let path = std::path::PathBuf::from(output);
-    if path.exists() {
-        if delete_without_confirm {
-            remove_dir_all(&path).context("Failed to remove dir")?;
-        } else {
-            print!("Path {:?} exists. Delete (yN)? ", path);
-            stdout().flush().unwrap();
-            let mut buffer = String::new();
-            stdin().read_line(&mut buffer)?;
-            if buffer == "y\n" || buffer == "Y\n" {
-                remove_dir_all(&path).context("Failed to remove dir")?;
-            } else {
-                anyhow::bail!("Path not deleted.");
-            }
-        }
-    }
-    create_dir(&path).context("Failed to create dir")?;
+    prepare_output_folder(&path, delete_without_confirm)?;
     for m in copy_files {
         let mut file =
             File::create(path.join(&*m.output_filename)).context("Failed to create copy file")?;
@@ -176,6 +199,7 @@ fn save_docs_to_folder(
                     item_provider,
                     base,
                     version_info.as_ref(),
+                    site,
                 )
             )
             .as_bytes(),
@@ -194,12 +218,7 @@ fn save_docs_to_folder(
         file.write_all(
             format!(
                 "<!DOCTYPE html>{}",
-                docs.render_summary_page(
-                    item_provider,
-                    base,
-                    version_info.as_ref(),
-                    canonical_domain.as_deref(),
-                )
+                docs.render_summary_page(item_provider, base, version_info.as_ref(), site,)
             )
             .as_bytes(),
         )
@@ -211,7 +230,7 @@ fn save_docs_to_folder(
         file.write_all(
             format!(
                 "<!DOCTYPE html>{}",
-                class.render(&docs.name, item_provider, base, version_info.as_ref())
+                class.render(&docs.name, item_provider, base, version_info.as_ref(), site,)
             )
             .as_bytes(),
         )
@@ -222,7 +241,7 @@ fn save_docs_to_folder(
             file.write_all(
                 format!(
                     "<!DOCTYPE html>{}",
-                    strukt.render(&docs.name, item_provider, base, version_info.as_ref())
+                    strukt.render(&docs.name, item_provider, base, version_info.as_ref(), site,)
                 )
                 .as_bytes(),
             )
@@ -233,7 +252,7 @@ fn save_docs_to_folder(
                 file.write_all(
                     format!(
                         "<!DOCTYPE html>{}",
-                        enm.render(&docs.name, item_provider, base, version_info.as_ref())
+                        enm.render(&docs.name, item_provider, base, version_info.as_ref(), site,)
                     )
                     .as_bytes(),
                 )
@@ -246,7 +265,7 @@ fn save_docs_to_folder(
             file.write_all(
                 format!(
                     "<!DOCTYPE html>{}",
-                    enm.render(&docs.name, item_provider, base, version_info.as_ref())
+                    enm.render(&docs.name, item_provider, base, version_info.as_ref(), site,)
                 )
                 .as_bytes(),
             )
@@ -259,7 +278,7 @@ fn save_docs_to_folder(
         file.write_all(
             format!(
                 "<!DOCTYPE html>{}",
-                strukt.render(&docs.name, item_provider, base, version_info.as_ref())
+                strukt.render(&docs.name, item_provider, base, version_info.as_ref(), site,)
             )
             .as_bytes(),
         )
@@ -270,7 +289,7 @@ fn save_docs_to_folder(
             file.write_all(
                 format!(
                     "<!DOCTYPE html>{}",
-                    enm.render(&docs.name, item_provider, base, version_info.as_ref())
+                    enm.render(&docs.name, item_provider, base, version_info.as_ref(), site,)
                 )
                 .as_bytes(),
             )
@@ -283,54 +302,251 @@ fn save_docs_to_folder(
         file.write_all(
             format!(
                 "<!DOCTYPE html>{}",
-                enm.render(&docs.name, item_provider, base, version_info.as_ref())
+                enm.render(&docs.name, item_provider, base, version_info.as_ref(), site,)
             )
             .as_bytes(),
         )
         .context("Failed to write enum file")?;
     }
-    for builtin in docs.builtins.iter() {
+    for builtin in docs.builtins.iter().flat_map(|b| b.with_nested()) {
         let mut file = File::create(path.join(format!("builtin.{}.html", builtin.name)))
             .context("Failed to create builtin file")?;
         file.write_all(
             format!(
                 "<!DOCTYPE html>{}",
-                builtin.render(&docs.name, item_provider, base, version_info.as_ref())
+                builtin.render(&docs.name, item_provider, base, version_info.as_ref(), site,)
             )
             .as_bytes(),
         )
         .context("Failed to write builtin file")?;
+        for enm in builtin.inner_enums.iter() {
+            let mut file = File::create(path.join(format!("enum.{}.html", enm.name)))
+                .context("Failed to create builtin inner enum file")?;
+            file.write_all(
+                format!(
+                    "<!DOCTYPE html>{}",
+                    enm.render(&docs.name, item_provider, base, version_info.as_ref(), site,)
+                )
+                .as_bytes(),
+            )
+            .context("Failed to write builtin inner enum file")?;
+        }
     }
     {
-        let mut file =
-            File::create(path.join("search.json")).context("Failed to create search json file")?;
-        file.write_all(
-            serde_json::to_string(&search::collect_search_results(docs, item_provider, base))
-                .unwrap()
-                .as_bytes(),
-        )
-        .context("Failed to write search json file")?;
+        let search::SearchResults {
+            index,
+            description_shards,
+        } = search::collect_search_results(docs, dependency_docs, item_provider, base);
+        // browsers refuse to `fetch` from `file://`, so offline builds load the index as scripts
+        write_search_data(
+            &path,
+            "search",
+            "window.zscdoc_search_results",
+            &serde_json::to_string(&index).unwrap(),
+            base.offline,
+            compress_search_index,
+        )?;
+        create_dir(path.join("search")).context("Failed to create search dir")?;
+        for (i, shard) in description_shards.iter().enumerate() {
+            write_search_data(
+                &path,
+                &format!("search/desc.{}", i),
+                &format!(
+                    "(window.zscdoc_search_shards = window.zscdoc_search_shards || {{}})[{}]",
+                    i
+                ),
+                &serde_json::to_string(shard).unwrap(),
+                base.offline,
+                compress_search_index,
+            )?;
+        }
+        write_search_data(
+            &path,
+            "fulltext",
+            "window.zscdoc_full_text_index",
+            &serde_json::to_string(&search::collect_full_text_index(docs, markdown_files)).unwrap(),
+            base.offline,
+            compress_search_index,
+        )?;
     }
+    write(
+        path.join("items.json"),
+        serde_json::to_string(&item_provider.inventory(&docs.name)).unwrap(),
+    )
+    .context("Failed to write inventory file")?;
     if let Some(f) = favicon {
         let mut file =
             File::create(path.join("favicon.png")).context("Failed to create favicon file")?;
         file.write_all(f).context("Failed to write favicon file")?;
     }
+    // crawlers can't do anything useful with a copy of the docs on someone's disk
+    if !base.offline {
+        let no_index = version_info
+            .as_ref()
+            .map(|v| v.no_index)
+            .unwrap_or_default();
+        if let (Some(domain), false) = (site.canonical_domain.as_deref(), no_index) {
+            write(
+                path.join("sitemap.xml"),
+                sitemap::render_sitemap(domain, base, &page_filenames(docs, markdown_files)),
+            )
+            .context("Failed to write sitemap file")?;
+        }
+        write(
+            path.join("robots.txt"),
+            sitemap::render_robots_txt(
+                site.canonical_domain.as_deref(),
+                base,
+                version_info.as_ref(),
+            ),
+        )
+        .context("Failed to write robots file")?;
+    }
     Ok(())
 }
 
-fn get_filesystem(path: &str) -> anyhow::Result<(GZDoomFolderFileSystem, Config, Vec<File>)> {
+/// The filenames of every HTML page written by `save_docs_to_folder`.
+fn page_filenames(
+    docs: &structures::Documentation,
+    markdown_files: &[MarkdownFileToRender],
+) -> Vec<String> {
+    let mut pages = vec!["index.html".to_string()];
+    pages.extend(markdown_files.iter().map(|m| m.output_filename.clone()));
+    let enum_pages = |enums: &[structures::Enum]| {
+        enums
+            .iter()
+            .map(|e| format!("enum.{}.html", e.name))
+            .collect_vec()
+    };
+    let struct_pages = |structs: &[structures::Struct]| {
+        structs
+            .iter()
+            .flat_map(|s| {
+                std::iter::once(format!("struct.{}.html", s.name)).chain(enum_pages(&s.inner_enums))
+            })
+            .collect_vec()
+    };
+    for class in docs.classes.iter() {
+        pages.push(format!("class.{}.html", class.name));
+        pages.extend(struct_pages(&class.inner_structs));
+        pages.extend(enum_pages(&class.inner_enums));
+    }
+    pages.extend(struct_pages(&docs.structs));
+    pages.extend(enum_pages(&docs.enums));
+    for builtin in docs.builtins.iter().flat_map(|b| b.with_nested()) {
+        pages.push(format!("builtin.{}.html", builtin.name));
+        pages.extend(enum_pages(&builtin.inner_enums));
+    }
+    // generated pages overwrite markdown files of the same name
+    pages.into_iter().unique().collect_vec()
+}
+
+/// Creates an empty output folder, asking before deleting anything already there.
+fn prepare_output_folder(
+    path: &std::path::Path,
+    delete_without_confirm: bool,
+) -> anyhow::Result<()> {
+    use std::fs::*;
+    use std::io::*;
+    if path.exists() {
+        if delete_without_confirm {
+            remove_dir_all(path).context("Failed to remove dir")?;
+        } else {
+            print!("Path {:?} exists. Delete (yN)? ", path);
+            stdout().flush().unwrap();
+            let mut buffer = String::new();
+            stdin().read_line(&mut buffer)?;
+            if buffer == "y\n" || buffer == "Y\n" {
+                remove_dir_all(path).context("Failed to remove dir")?;
+            } else {
+                anyhow::bail!("Path not deleted.");
+            }
+        }
+    }
+    create_dir(path).context("Failed to create dir")?;
+    Ok(())
+}
+
+fn save_wiki_to_folder(
+    output: &str,
+    docs: &structures::Documentation,
+    delete_without_confirm: bool,
+    item_provider: &ItemProvider,
+    syntax: &dyn wiki::WikiSyntax,
+) -> anyhow::Result<()> {
+    let path = std::path::PathBuf::from(output);
+    prepare_output_folder(&path, delete_without_confirm)?;
+    for (filename, contents) in wiki::render_pages(docs, item_provider, syntax) {
+        std::fs::write(path.join(&filename), contents)
+            .context(format!("Failed to write wiki page {:?}", filename))?;
+    }
+    Ok(())
+}
+
+/// Writes `{stem}.json`, or for offline builds `{stem}.js` assigning the data to `js_target`.
+fn write_search_data(
+    path: &std::path::Path,
+    stem: &str,
+    js_target: &str,
+    json: &str,
+    offline: bool,
+    compress: bool,
+) -> anyhow::Result<()> {
+    use std::io::Write;
+
+    let (filename, contents) = if offline {
+        (format!("{}.js", stem), format!("{} = {};", js_target, json))
+    } else {
+        (format!("{}.json", stem), json.to_string())
+    };
+    std::fs::write(path.join(&filename), &contents)
+        .context(format!("Failed to write search file {:?}", filename))?;
+    if compress {
+        let file = std::fs::File::create(path.join(format!("{}.gz", filename))).context(
+            format!("Failed to create compressed search file {:?}", filename),
+        )?;
+        let mut encoder = flate2::write::GzEncoder::new(file, flate2::Compression::best());
+        encoder.write_all(contents.as_bytes()).context(format!(
+            "Failed to write compressed search file {:?}",
+            filename
+        ))?;
+        encoder.finish().context(format!(
+            "Failed to finish compressed search file {:?}",
+            filename
+        ))?;
+    }
+    Ok(())
+}
+
+/// Loads an archive and its `docs/zscdoc.toml`. Dependencies may lack one, like a stock
+/// gzdoom.pk3, in which case they're given a config with the defaults and `default_name`.
+fn get_filesystem(
+    path: &str,
+    default_name: Option<&str>,
+) -> anyhow::Result<(GZDoomFolderFileSystem, Config, Vec<File>)> {
     use anyhow::Context;
 
     let mut filesystem = GZDoomFolderFileSystem::new(path.to_string(), path.to_string())
         .context("couldn't load a path")?;
 
-    let config_file = filesystem
-        .get_file("docs/zscdoc.toml")
-        .context("couldn't find zscdoc.toml")?;
-    let config_file = config_file.text();
-
-    let config: Config = toml::from_str(config_file).context("config file parsing failed")?;
+    let config: Config = match (filesystem.get_file("docs/zscdoc.toml"), default_name) {
+        (Some(config_file), _) => {
+            toml::from_str(config_file.text()).context("config file parsing failed")?
+        }
+        (None, Some(name)) => {
+            let archive = toml::value::Table::from_iter([(
+                "nice_name".to_string(),
+                toml::Value::String(name.to_string()),
+            )]);
+            Config {
+                archive: toml::Value::Table(archive)
+                    .try_into()
+                    .context("default config couldn't be made")?,
+                dependency: None,
+            }
+        }
+        (None, None) => anyhow::bail!("couldn't find zscdoc.toml"),
+    };
 
     let mut filesystem =
         GZDoomFolderFileSystem::new(path.to_string(), config.archive.nice_name.clone())
@@ -360,65 +576,208 @@ pub fn option_slice_to_slice<T>(v: Option<&[T]>) -> &[T] {
     v.unwrap_or(&[])
 }
 
-fn get_builtins(files: &[File]) -> anyhow::Result<impl Iterator<Item = BuiltinTypeFromFile> + '_> {
+fn get_builtins(files: &[File]) -> anyhow::Result<impl Iterator<Item = BuiltinSource> + '_> {
     use anyhow::Context;
 
     let r: anyhow::Result<Vec<_>> = files
         .iter()
         .map(|f| {
+            let extension = std::path::Path::new(f.filename())
+                .extension()
+                .and_then(|e| e.to_str())
+                .map(|e| e.to_lowercase());
+            if let Some("zs" | "zsc" | "zc") = extension.as_deref() {
+                return Ok(BuiltinSource::Declarations(BuiltinDeclarationFile {
+                    filename: f.filename().to_string(),
+                    text: f.text().to_string(),
+                }));
+            }
             let mut builtin: BuiltinTypeFromFile =
                 toml::from_str(f.text()).context("builtin file parsing failed")?;
             builtin.filename = f.filename().to_string();
-            Ok(builtin)
+            Ok(BuiltinSource::Toml(builtin))
         })
         .collect();
     r.map(|r| r.into_iter())
 }
 
+struct CollectedInventory {
+    inventory: structures::Inventory,
+    url: String,
+}
+
+fn load_inventory(path: &std::path::Path) -> anyhow::Result<structures::Inventory> {
+    let path = if path.is_dir() {
+        path.join("items.json")
+    } else {
+        path.to_path_buf()
+    };
+    let text =
+        std::fs::read_to_string(&path).context(format!("couldn't read inventory {:?}", path))?;
+    serde_json::from_str(&text).context(format!("inventory {:?} couldn't be parsed", path))
+}
+
+/// The inventory dependency describing a top-level class or struct called `name`, if there is
+/// one. Inventories carry no source, so anything that needs such an item's HIR can't use it.
+fn inventory_with_type(
+    inventories: &[CollectedInventory],
+    name: zscript_parser::interner::NameSymbol,
+) -> Option<&CollectedInventory> {
+    inventories.iter().find(|i| {
+        i.inventory.items.iter().any(|item| {
+            matches!(
+                item.kind,
+                structures::LinkedSectionKind::Class { .. }
+                    | structures::LinkedSectionKind::Struct { .. }
+            ) && item.path.len() == 1
+                && zscript_parser::interner::intern_name(&item.path[0]) == name
+        })
+    })
+}
+
+/// Fails if a class inherits from a class that only an inventory dependency knows about, since
+/// walking its ancestors needs their source.
+fn check_ancestors_not_in_inventories(
+    hir: &zscript_parser::hir::TopLevel,
+    files: &Files,
+    inventories: &[CollectedInventory],
+) -> anyhow::Result<()> {
+    for d in hir.definitions.values().flatten() {
+        let c = match &d.kind {
+            zscript_parser::hir::TopLevelDefinitionKind::Class(c) => c,
+            _ => continue,
+        };
+        let ancestor = match c.ancestor {
+            Some(a) if !hir.definitions.contains_key(&a.symbol) => a,
+            _ => continue,
+        };
+        if let Some(i) = inventory_with_type(inventories, ancestor.symbol) {
+            anyhow::bail!(
+                "class `{}` inherits from `{}`, which only the inventory dependency `{}` provides; \
+                inventories don't include source, so give that dependency by `path` or `git` instead",
+                files.text_from_span(c.name.span),
+                files.text_from_span(ancestor.span),
+                i.inventory.name
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Fails if the builtin `b`, or one of its structs, `uses` a struct that only an inventory
+/// dependency knows about, since its members are copied from the struct's source.
+fn check_uses_not_in_inventories(
+    b: &BuiltinTypeHir,
+    hir: &zscript_parser::hir::TopLevel,
+    inventories: &[CollectedInventory],
+) -> anyhow::Result<()> {
+    for s in b.structs.iter() {
+        check_uses_not_in_inventories(s, hir, inventories)?;
+    }
+    let n = match &b.uses_things_from {
+        Some(n) => n,
+        None => return Ok(()),
+    };
+    let ns = zscript_parser::interner::intern_name(n);
+    if !hir.definitions.contains_key(&ns) {
+        if let Some(i) = inventory_with_type(inventories, ns) {
+            anyhow::bail!(
+                "builtin `{}` uses things from `{}`, which only the inventory dependency `{}` \
+                provides; inventories don't include source, so give that dependency by `path` or \
+                `git` instead",
+                b.name,
+                n,
+                i.inventory.name
+            );
+        }
+    }
+    Ok(())
+}
+
 struct CollectedDependency {
+    path: std::path::PathBuf,
     filesystem: GZDoomFolderFileSystem,
     config: Config,
     url: String,
-    builtins: Vec<BuiltinTypeFromFile>,
+    builtins: Vec<BuiltinSource>,
 }
 
 fn collect_dependencies(
     dependencies: &[Dependency],
-    base_path: &str,
-) -> anyhow::Result<Vec<CollectedDependency>> {
+    base_path: &std::path::Path,
+    locker: &mut git::Locker,
+) -> anyhow::Result<(Vec<CollectedDependency>, Vec<CollectedInventory>)> {
     use anyhow::Context;
     use std::collections::HashSet;
     fn recurse(
         dependencies: &[Dependency],
         ret: &mut Vec<CollectedDependency>,
+        inventories: &mut Vec<CollectedInventory>,
         seen: &mut HashSet<String>,
         base_path: &std::path::Path,
+        locker: &mut git::Locker,
     ) -> anyhow::Result<()> {
         for d in dependencies.iter() {
             let dep_path = match &d.find_at {
                 DependencyPathKind::Path { path } => base_path.join(path),
-                DependencyPathKind::Git { git, refname, base } => {
+                DependencyPathKind::Inventory { inventory } => {
+                    let inventory = load_inventory(&base_path.join(inventory))?;
+                    if seen.insert(inventory.name.clone()) {
+                        inventories.push(CollectedInventory {
+                            inventory,
+                            url: d.url.to_string(),
+                        });
+                    }
+                    continue;
+                }
+                DependencyPathKind::Git {
+                    git,
+                    refname,
+                    tag,
+                    rev,
+                    base,
+                } => {
+                    let reference = git::GitRef::from_options(
+                        refname.as_deref(),
+                        tag.as_deref(),
+                        rev.as_deref(),
+                    )
+                    .context(format!("dependency {:?}", git))?;
                     eprintln!(
-                        "Cloning git repository for dependency: {}, ref {}...",
-                        git, refname
+                        "Cloning git repository for dependency: {}, {}...",
+                        git, reference
                     );
-                    git::clone_git(git, refname)
+                    locker
+                        .clone_git(git, &reference)
                         .context("git cloning failed")?
                         .join(base)
                 }
             };
-            let dep_path_str = dep_path.to_str().context("paths must be UTF-8")?;
-            let (filesystem, config, builtin_files) = get_filesystem(dep_path_str)
+            // named after what the config points at, since packed archives are read from a
+            // folder named after their hash
+            let default_name = dep_path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .map(|s| s.to_string());
+            let archive::ArchivePaths {
+                folder: dep_path,
+                relative_to,
+            } = archive::unpack(&dep_path)
                 .context(format!("loading dependency path {:?}", dep_path))?;
+            let dep_path_str = dep_path.to_str().context("paths must be UTF-8")?;
+            let (filesystem, config, builtin_files) =
+                get_filesystem(dep_path_str, default_name.as_deref())
+                    .context(format!("loading dependency path {:?}", dep_path))?;
             if seen.contains(&config.archive.nice_name) {
                 continue;
             }
             seen.insert(config.archive.nice_name.to_string());
 
             let dependencies = option_slice_to_slice(config.dependency.as_deref());
-            recurse(dependencies, ret, seen, &dep_path)?;
+            recurse(dependencies, ret, inventories, seen, &relative_to, locker)?;
             let builtins = get_builtins(&builtin_files)?.collect_vec();
             ret.push(CollectedDependency {
+                path: dep_path,
                 filesystem,
                 config,
                 url: d.url.to_string(),
@@ -430,24 +789,51 @@ fn collect_dependencies(
     }
 
     let mut ret = vec![];
+    let mut inventories = vec![];
     recurse(
         dependencies,
         &mut ret,
+        &mut inventories,
         &mut HashSet::new(),
-        std::path::Path::new(base_path),
+        base_path,
+        locker,
     )?;
-    Ok(ret)
+    Ok((ret, inventories))
 }
 
 fn main() -> anyhow::Result<()> {
     use anyhow::Context;
 
     let args = Args::parse();
+    match &args.command {
+        Some(Command::Cache { action }) => return cache::run(action),
+        Some(Command::CheckConfig { schema: true }) => return check_config::print_schema(),
+        _ => {}
+    }
+    let Some(folder) = args.folder.as_deref() else {
+        anyhow::bail!("`--folder` must be given");
+    };
+    match &args.command {
+        Some(Command::CheckConfig { .. }) => return check_config::check(folder),
+        Some(Command::Init { name }) => return init::init(folder, name.as_deref()),
+        _ => {}
+    }
+    if args.command.is_none() && args.output.is_none() && args.coverage.is_none() {
+        anyhow::bail!("one of `--output`, `--coverage` or a subcommand must be given");
+    }
 
     let mut files = Files::default();
 
-    let (mut filesystem, config, builtin_files) =
-        get_filesystem(&args.folder).context("loading main archive")?;
+    let archive_paths =
+        archive::unpack(std::path::Path::new(folder)).context("loading main archive")?;
+    let (mut filesystem, config, builtin_files) = get_filesystem(
+        archive_paths
+            .folder
+            .to_str()
+            .context("paths must be UTF-8")?,
+        None,
+    )
+    .context("loading main archive")?;
 
     let summary_doc = filesystem
         .get_file("docs/summary.md")
@@ -457,6 +843,17 @@ fn main() -> anyhow::Result<()> {
     let favicon = filesystem.get_file("docs/favicon.png");
     let favicon = favicon.as_ref().map(|s| s.data());
 
+    let [header, footer, sidebar] = ["header", "footer", "sidebar"].map(|name| {
+        filesystem
+            .get_file(&format!("docs/templates/{}.html", name))
+            .map(|s| s.text().to_string())
+    });
+    let templates = Templates {
+        header,
+        footer,
+        sidebar,
+    };
+
     let markdown_files: Result<Vec<_>, _> = option_vec_to_vec(config.archive.markdown_files)
         .iter()
         .map(|m| {
@@ -478,23 +875,56 @@ fn main() -> anyhow::Result<()> {
         .collect();
     let markdown_files = markdown_files?;
 
+    let stylesheets = option_vec_to_vec(config.archive.theme.stylesheets);
+    let scripts = option_vec_to_vec(config.archive.theme.scripts);
     let copy_files: Result<Vec<_>, anyhow::Error> = option_vec_to_vec(config.archive.copy_files)
-        .iter()
-        .map(|m| {
-            let output_filename = m.filename.clone();
-            let filename_to_get = format!("docs/{}", m.filename);
+        .into_iter()
+        .map(|m| m.filename)
+        .chain(config.archive.site_image.clone())
+        .chain(stylesheets.iter().cloned())
+        .chain(scripts.iter().cloned())
+        .map(|filename| {
+            let filename_to_get = format!("docs/{}", filename);
             let file = filesystem
                 .get_file(&filename_to_get)
                 .context(format!("file {:?} didn't exist", filename_to_get))?;
             Ok(CopyFileToRender {
-                output_filename,
+                output_filename: filename,
                 bytes: file.data().to_vec(),
             })
         })
         .collect();
     let copy_files = copy_files?;
 
-    let depedencies = collect_dependencies(&option_vec_to_vec(config.dependency), &args.folder)?;
+    // the lockfile can only be kept up to date when the archive is a folder
+    let lockfile_path = std::path::Path::new(folder)
+        .is_dir()
+        .then(|| std::path::Path::new(folder).join("docs/zscdoc.lock"));
+    let previous_lockfile = match &lockfile_path {
+        Some(p) if p.exists() => {
+            toml::from_str(&std::fs::read_to_string(p).context("couldn't read zscdoc.lock")?)
+                .context("lockfile parsing failed")?
+        }
+        _ => git::Lockfile::default(),
+    };
+    let mut locker = git::Locker::new(previous_lockfile, args.update_deps, args.offline_deps);
+    let (depedencies, inventories) = collect_dependencies(
+        &option_vec_to_vec(config.dependency),
+        &archive_paths.relative_to,
+        &mut locker,
+    )?;
+    let lockfile = locker.finish();
+    if let Some(p) = lockfile_path {
+        if !lockfile.is_empty() || p.exists() {
+            std::fs::write(&p, toml::to_string(&lockfile)?)
+                .context("couldn't write zscdoc.lock")?;
+        }
+    }
+
+    // the language server needs these to turn spans back into paths
+    let archive_folders = std::iter::once(archive_paths.folder.clone())
+        .chain(depedencies.iter().map(|d| d.path.clone()))
+        .collect_vec();
 
     let mut errs = vec![];
 
@@ -502,6 +932,7 @@ fn main() -> anyhow::Result<()> {
     let (mut parsed_vec, dependency_links, mut builtins): (Vec<_>, Vec<_>, Vec<_>) =
         itertools::multiunzip(depedencies.into_iter().map(|d| {
             let CollectedDependency {
+                path: _,
                 filesystem,
                 config,
                 url,
@@ -512,7 +943,10 @@ fn main() -> anyhow::Result<()> {
             };
             (
                 parse_filesystem_config(filesystem, &mut files, &mut errs, &options),
-                crate::structures::Dependency { link: url },
+                crate::structures::Dependency {
+                    name: config.archive.nice_name.clone(),
+                    link: url,
+                },
                 builtins,
             )
         }));
@@ -526,6 +960,7 @@ fn main() -> anyhow::Result<()> {
         filesystem, &mut files, &mut errs, &options,
     ));
     let hir = HirLowerer::new(&mut errs).lower(parsed_vec).hir;
+    check_ancestors_not_in_inventories(&hir, &files, &inventories)?;
 
     if !errs.is_empty() {
         return Err(anyhow::anyhow!(errs.to_displayed_errors(&files)))
@@ -541,21 +976,25 @@ fn main() -> anyhow::Result<()> {
         .map(|b| {
             b.into_iter()
                 .map(|b| b.produce(&mut files))
-                .collect::<Result<Vec<_>, _>>()
+                .flatten_ok()
+                .collect::<anyhow::Result<Vec<_>>>()
         })
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|x| x.to_displayed_errors(&files))?;
+        .collect::<anyhow::Result<Vec<_>>>()?;
 
     for b in builtins.iter_mut() {
         for b in b.iter_mut() {
+            check_uses_not_in_inventories(b, &hir, &inventories)?;
             b.extend_with_uses_things_from(&hir)?;
         }
     }
 
     item_provider.add_builtins(&builtins, &files, &dependencies);
+    for i in inventories.iter() {
+        item_provider.add_inventory(&i.inventory, &i.url);
+    }
     let item_provider = item_provider;
 
-    let builtins = builtins
+    let main_builtins = builtins
         .pop()
         .unwrap()
         .into_iter()
@@ -569,12 +1008,50 @@ fn main() -> anyhow::Result<()> {
         &hir,
         &files,
         &item_provider,
-        &dependencies,
-        builtins,
+        dependencies.get_final_archive_num(),
+        main_builtins,
         config.archive.document_globals,
     );
 
-    let base_url = args.base_url.unwrap_or(config.archive.base_url);
+    // `show` and the language server need these to look up items from dependencies
+    let looks_up_items = matches!(args.command, Some(Command::Show { .. } | Command::Lsp));
+    let mut dependency_docs = vec![];
+    if config.archive.search_dependencies || looks_up_items {
+        eprintln!("Generating dependency documentation structures...");
+        for (archive_num, builtins) in builtins.into_iter().enumerate() {
+            let builtins = builtins
+                .into_iter()
+                .map(|b| b.produce(&mut files, &item_provider))
+                .collect_vec();
+            let dependency = &dependencies.dependency_links[archive_num];
+            dependency_docs.push(structures::DependencyDocumentation {
+                docs: document::hir_to_doc_structures(
+                    String::new(),
+                    &dependency.name,
+                    &hir,
+                    &files,
+                    &item_provider,
+                    archive_num,
+                    builtins,
+                    false,
+                ),
+                link: dependency.link.clone(),
+            });
+        }
+    }
+
+    let base_url = if args.offline {
+        if !matches!(args.format, OutputFormat::Html) {
+            anyhow::bail!("`--offline` can only be used with the HTML format");
+        }
+        if args.base_url.is_some() {
+            anyhow::bail!("`--base-url` can't be used with `--offline`");
+        }
+        // every page lives in the same folder, so this makes all generated links relative
+        ".".to_string()
+    } else {
+        args.base_url.unwrap_or(config.archive.base_url)
+    };
 
     let versions: Option<Vec<VersionItem>> = args
         .versions
@@ -583,6 +1060,9 @@ fn main() -> anyhow::Result<()> {
         .transpose()?;
 
     let version_info = if versions.is_some() || args.target_version.is_some() {
+        if args.offline {
+            anyhow::bail!("version support can't be used with `--offline`")
+        }
         let Some(versions) = versions else {
             anyhow::bail!("`--versions` must be present if `--target-version` is")
         };
@@ -622,9 +1102,32 @@ fn main() -> anyhow::Result<()> {
                 .map(|v| v.current.clone())
                 .unwrap_or_else(|| "<version>".to_string()),
         ),
+        offline: args.offline,
     };
 
-    if let Some(c) = args.coverage {
+    let site = SiteInfo {
+        canonical_domain: args.canonical_domain,
+        site_image: config.archive.site_image,
+        builtin_theme: config.archive.theme.builtin,
+        stylesheets,
+        scripts,
+        nav_links: option_vec_to_vec(config.archive.nav_links),
+        templates,
+    };
+
+    let all_docs = show::archive_docs(&docs, &dependency_docs);
+    if let Some(Command::Show { path, url }) = &args.command {
+        show::show_item(
+            path,
+            *url,
+            all_docs,
+            &item_provider,
+            &base_url,
+            site.canonical_domain.as_deref(),
+        )?;
+    } else if let Some(Command::Lsp) = &args.command {
+        lsp::run(&all_docs, &item_provider, &files, archive_folders)?;
+    } else if let Some(c) = args.coverage {
         let breakdown = coverage_breakdown(
             docs.coverage(&config.archive.nice_name, &files)
                 .collect_vec(),
@@ -632,18 +1135,36 @@ fn main() -> anyhow::Result<()> {
         breakdown.show(c);
     } else {
         let out = args.output.unwrap();
-        save_docs_to_folder(
-            &out,
-            &docs,
-            args.delete_without_confirm,
-            &item_provider,
-            favicon,
-            &markdown_files,
-            &copy_files,
-            &base_url,
-            version_info,
-            args.canonical_domain,
-        )
+        match args.format {
+            OutputFormat::Html => save_docs_to_folder(
+                &out,
+                &docs,
+                &dependency_docs,
+                args.delete_without_confirm,
+                &item_provider,
+                favicon,
+                &markdown_files,
+                &copy_files,
+                &base_url,
+                version_info,
+                &site,
+                args.compress_search_index,
+            ),
+            OutputFormat::Markdown => save_wiki_to_folder(
+                &out,
+                &docs,
+                args.delete_without_confirm,
+                &item_provider,
+                &wiki::GithubMarkdown,
+            ),
+            OutputFormat::Mediawiki => save_wiki_to_folder(
+                &out,
+                &docs,
+                args.delete_without_confirm,
+                &item_provider,
+                &wiki::MediaWiki,
+            ),
+        }
         .context("Failed to write docs to folder")?;
         eprintln!("Documentation written to {}!", out);
     }
@@ -52,22 +52,88 @@ fn title_name(docs_name: &str, version_info: Option<&VersionInfo>) -> String {
     )
 }
 
+/// Fills in the `{{title}}`, `{{version}}` and `{{base_url}}` variables of a template fragment.
+fn fill_template(
+    template: &str,
+    title: &str,
+    base: &BaseUrl,
+    version_info: Option<&VersionInfo>,
+) -> String {
+    let escape = |s: &str| {
+        let mut escaped = String::new();
+        pulldown_cmark::escape::escape_html(&mut escaped, s).unwrap();
+        escaped
+    };
+    let version = version_info
+        .and_then(|v| v.versions.iter().find(|i| i.url_part == v.current))
+        .map(|v| v.nice_name.as_str())
+        .unwrap_or_default();
+    template
+        .replace("{{title}}", &escape(title))
+        .replace("{{version}}", &escape(version))
+        .replace("{{base_url}}", &escape(&base.filled))
+}
+
 fn render_html_boilerplate(
     title: &str,
     body: Box<dyn FlowContent<String>>,
     sidebar_data: SidebarData,
-    canonical_url: Option<&str>,
+    href: &str,
+    description: Option<String>,
+    site: &SiteInfo,
     base: &BaseUrl,
     version_info: Option<&VersionInfo>,
 ) -> DOMTree<String> {
+    let canonical_url = canonical_url(site, href, base, version_info);
+    let site_name = format!("{} Documentation", sidebar_data.docs_name);
+    let image_url = site
+        .site_image
+        .as_ref()
+        .map(|i| match &site.canonical_domain {
+            Some(d) => format!("{}{}/{}", d, base.filled, i),
+            None => prefix_href(&base.filled, &format!("/{}", i)),
+        });
     html!(
         <html lang="en-US">
             <head>
                 <title> { text!(title) } </title>
                 <link rel="icon" type="image/x-icon" href={ prefix_href(&base.filled, "/favicon.png") }/>
                 <link rel="stylesheet" href={ prefix_href(&base.filled, "/main.css") }/>
-                { canonical_url.map(|c| html!(<link rel="canonical" href={ c }/>)) }
+                { match site.builtin_theme {
+                    BuiltinTheme::Light => None,
+                    BuiltinTheme::Dark => Some(html!(
+                        <link rel="stylesheet" href={ prefix_href(&base.filled, "/dark.css") }/>
+                    )),
+                    BuiltinTheme::Auto => Some(html!(
+                        <link
+                            rel="stylesheet"
+                            href={ prefix_href(&base.filled, "/dark.css") }
+                            media="(prefers-color-scheme: dark)"
+                        />
+                    )),
+                } }
+                { site.stylesheets.iter().map(|s| html!(
+                    <link rel="stylesheet" href={ prefix_href(&base.filled, &format!("/{}", s)) }/>
+                )) }
+                { canonical_url.as_ref().map(|c| html!(<link rel="canonical" href={ c.as_str() }/>)) }
+                { description.as_ref().map(|d| html!(<meta name="description" content={ d.as_str() }/>)) }
+                <meta property="og:type" content="website"/>
+                <meta property="og:site_name" content={ site_name.as_str() }/>
+                <meta property="og:title" content={ title }/>
+                { description.as_ref().map(|d| html!(<meta property="og:description" content={ d.as_str() }/>)) }
+                { canonical_url.as_ref().map(|c| html!(<meta property="og:url" content={ c.as_str() }/>)) }
+                { image_url.as_ref().map(|i| html!(<meta property="og:image" content={ i.as_str() }/>)) }
+                <meta name="twitter:card" content="summary"/>
+                <meta name="twitter:title" content={ title }/>
+                { description.as_ref().map(|d| html!(<meta name="twitter:description" content={ d.as_str() }/>)) }
+                { image_url.as_ref().map(|i| html!(<meta name="twitter:image" content={ i.as_str() }/>)) }
+                { if base.offline {
+                    Some(html!(<script src="search.js"></script>))
+                } else { None } }
                 <script src="main.bundle.js"></script>
+                { site.scripts.iter().map(|s| html!(
+                    <script src={ prefix_href(&base.filled, &format!("/{}", s)) }></script>
+                )) }
                 <meta charset="UTF-8"/>
                 <meta name="viewport" content="width=device-width, initial-scale=1.0"/>
                 { if version_info.map(|v| v.no_index).unwrap_or_default() {
@@ -83,12 +149,22 @@ fn render_html_boilerplate(
                     { version_info.as_ref().map(|v| version_selector(v, base, Some("Version"), "id_selector_header")) }
                 </div>
                 <div id="not_header">
-                    { render_sidebar(sidebar_data, base, version_info) }
+                    { render_sidebar(sidebar_data, title, site, base, version_info) }
                     <div id="inner">
+                        { site.templates.header.as_ref().map(|t| html!(
+                            <div id="page_header">
+                                { unsafe_text!(fill_template(t, title, base, version_info)) }
+                            </div>
+                        )) }
                         <div id="search">
                             <input id="search_input" placeholder="Search"/>
                         </div>
                         { body }
+                        { site.templates.footer.as_ref().map(|t| html!(
+                            <div id="page_footer">
+                                { unsafe_text!(fill_template(t, title, base, version_info)) }
+                            </div>
+                        )) }
                     </div>
                 </div>
             </body>
@@ -170,6 +246,68 @@ fn broken_link_callback<'a>(
     }
 }
 
+/// Cuts a markdown event stream off after its first block, which becomes the summary of an item.
+fn first_paragraph<'a>(
+    parser: impl Iterator<Item = pulldown_cmark::Event<'a>>,
+) -> impl Iterator<Item = pulldown_cmark::Event<'a>> {
+    use pulldown_cmark::{Event, Tag};
+
+    struct ScannerState {
+        level: usize,
+        started: bool,
+    }
+    parser.scan(
+        ScannerState {
+            level: 0,
+            started: false,
+        },
+        |state, event| {
+            fn should_stop(tag: &Tag) -> bool {
+                matches!(
+                    tag,
+                    Tag::CodeBlock(..)
+                        | Tag::Table(..)
+                        | Tag::TableHead
+                        | Tag::TableRow
+                        | Tag::TableCell
+                )
+            }
+            fn map_tag(tag: Tag) -> Tag {
+                match tag {
+                    t @ (Tag::Paragraph
+                    | Tag::BlockQuote
+                    | Tag::Item
+                    | Tag::Emphasis
+                    | Tag::Strong
+                    | Tag::Link(..)) => t,
+                    _ => Tag::Paragraph,
+                }
+            }
+            if state.started && state.level == 0 {
+                return None;
+            }
+            state.started = true;
+            match event {
+                Event::Start(t) => {
+                    if should_stop(&t) {
+                        return None;
+                    }
+                    state.level += 1;
+                    Some(Event::Start(map_tag(t)))
+                }
+                Event::End(t) => {
+                    if should_stop(&t) {
+                        return None;
+                    }
+                    state.level -= 1;
+                    Some(Event::End(map_tag(t)))
+                }
+                e => Some(e),
+            }
+        },
+    )
+}
+
 pub fn render_doc_summary(
     text: &str,
     item_provider: &ItemProvider,
@@ -179,64 +317,6 @@ pub fn render_doc_summary(
     if text.trim().is_empty() {
         return None;
     }
-    use pulldown_cmark::{Event, Tag};
-
-    fn map<'a>(parser: impl Iterator<Item = Event<'a>>) -> impl Iterator<Item = Event<'a>> {
-        struct ScannerState {
-            level: usize,
-            started: bool,
-        }
-        parser.scan(
-            ScannerState {
-                level: 0,
-                started: false,
-            },
-            |state, event| {
-                fn should_stop(tag: &Tag) -> bool {
-                    matches!(
-                        tag,
-                        Tag::CodeBlock(..)
-                            | Tag::Table(..)
-                            | Tag::TableHead
-                            | Tag::TableRow
-                            | Tag::TableCell
-                    )
-                }
-                fn map_tag(tag: Tag) -> Tag {
-                    match tag {
-                        t @ (Tag::Paragraph
-                        | Tag::BlockQuote
-                        | Tag::Item
-                        | Tag::Emphasis
-                        | Tag::Strong
-                        | Tag::Link(..)) => t,
-                        _ => Tag::Paragraph,
-                    }
-                }
-                if state.started && state.level == 0 {
-                    return None;
-                }
-                state.started = true;
-                match event {
-                    Event::Start(t) => {
-                        if should_stop(&t) {
-                            return None;
-                        }
-                        state.level += 1;
-                        Some(Event::Start(map_tag(t)))
-                    }
-                    Event::End(t) => {
-                        if should_stop(&t) {
-                            return None;
-                        }
-                        state.level -= 1;
-                        Some(Event::End(map_tag(t)))
-                    }
-                    e => Some(e),
-                }
-            },
-        )
-    }
     let html_output = {
         let dedented = textwrap::dedent(text);
 
@@ -250,7 +330,7 @@ pub fn render_doc_summary(
         )
         .map(|x| md_event_map(x, Some((item_provider, context)), base));
 
-        let parser = map(parser);
+        let parser = first_paragraph(parser);
 
         let mut html_output = String::new();
         html::push_html(&mut html_output, parser);
@@ -265,6 +345,78 @@ pub fn render_doc_summary(
     ))
 }
 
+/// The summary of a doc comment as plain text, for use in `<meta>` tags.
+fn render_doc_summary_text(
+    text: &str,
+    item_provider: &ItemProvider,
+    context: &[zscript_parser::interner::NameSymbol],
+    base: &BaseUrl,
+) -> Option<String> {
+    use pulldown_cmark::Event;
+
+    let dedented = textwrap::dedent(text);
+    let mut broken_link_callback = |x| broken_link_callback(x, item_provider, context, base);
+    let parser = Parser::new_with_broken_link_callback(
+        &dedented,
+        Options::ENABLE_TABLES,
+        Some(&mut broken_link_callback),
+    );
+    let mut summary = String::new();
+    for event in first_paragraph(parser) {
+        match event {
+            Event::Text(t) | Event::Code(t) | Event::Html(t) => summary += &t,
+            Event::SoftBreak | Event::HardBreak => summary += " ",
+            _ => {}
+        }
+    }
+    let summary = summary.split_whitespace().join(" ");
+    (!summary.is_empty()).then_some(summary)
+}
+
+/// The anchors given to each heading of a large doc comment, in order of appearance.
+///
+/// These are derived GitHub-style from the heading text, with duplicates being numbered, and are
+/// prefixed with `id`, the id of the doc comment's element, so that they stay unique when a page
+/// shows several doc comments.
+pub fn heading_slugs(text: &str, id: &str) -> Vec<String> {
+    use pulldown_cmark::{Event, Tag};
+
+    let dedented = textwrap::dedent(text);
+    let mut slugs = vec![];
+    let mut seen = std::collections::HashMap::<String, usize>::new();
+    let mut cur_heading = None;
+    for event in Parser::new_ext(&dedented, Options::ENABLE_TABLES) {
+        match event {
+            Event::Start(Tag::Heading(..)) => {
+                cur_heading = Some(String::new());
+            }
+            Event::End(Tag::Heading(..)) => {
+                let heading = cur_heading.take().unwrap_or_default();
+                let slug = heading
+                    .to_lowercase()
+                    .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+                    .filter(|s| !s.is_empty())
+                    .join("-");
+                let count = seen.entry(slug.clone()).or_default();
+                let slug = if *count == 0 {
+                    format!("{id}.section.{slug}")
+                } else {
+                    format!("{id}.section.{slug}-{count}")
+                };
+                *count += 1;
+                slugs.push(slug);
+            }
+            Event::Text(t) | Event::Code(t) => {
+                if let Some(h) = &mut cur_heading {
+                    *h += &t;
+                }
+            }
+            _ => {}
+        }
+    }
+    slugs
+}
+
 fn render_doc_comment(
     text: &str,
     large: bool,
@@ -278,17 +430,35 @@ fn render_doc_comment(
     }
 
     let html_output = {
+        use pulldown_cmark::{Event, Tag};
+
         let dedented = textwrap::dedent(text);
 
         let options = Options::ENABLE_TABLES;
 
+        // headings in large doc comments get anchors so that the full-text search can link to them
+        let slugs = if large {
+            heading_slugs(text, id)
+        } else {
+            vec![]
+        };
+        let mut slugs_iter = slugs.iter();
+
         let mut broken_link_callback = |x| broken_link_callback(x, item_provider, context, base);
         let parser = Parser::new_with_broken_link_callback(
             &dedented,
             options,
             Some(&mut broken_link_callback),
         )
-        .map(|x| md_event_map(x, Some((item_provider, context)), base));
+        .map(|x| md_event_map(x, Some((item_provider, context)), base))
+        .map(|x| match x {
+            Event::Start(Tag::Heading(level, None, classes)) => Event::Start(Tag::Heading(
+                level,
+                slugs_iter.next().map(|s| s.as_str()),
+                classes,
+            )),
+            e => e,
+        });
 
         let mut html_output = String::new();
         html::push_html(&mut html_output, parser);
@@ -334,7 +504,7 @@ impl LinkedSectionKind {
         }
     }
 
-    fn get_kind_href(&self) -> String {
+    pub fn get_kind_href(&self) -> String {
         match &self {
             LinkedSectionKind::Struct { link } => format!("/struct.{}.html", link.join(".")),
             LinkedSectionKind::Class { link } => format!("/class.{}.html", link.join(".")),
@@ -366,6 +536,22 @@ impl LinkedSectionKind {
     }
 }
 
+/// The URL search engines should treat as the original of `href`, which is the same page in the
+/// latest version when version support is active.
+fn canonical_url(
+    site: &SiteInfo,
+    href: &str,
+    base: &BaseUrl,
+    version_info: Option<&VersionInfo>,
+) -> Option<String> {
+    let domain = site.canonical_domain.as_deref()?;
+    let base_url = match version_info.and_then(|v| v.versions.iter().find(|v| v.latest)) {
+        Some(latest) => base.template.replace("<version>", &latest.url_part),
+        None => base.filled.clone(),
+    };
+    Some(format!("{}{}", domain, prefix_href(&base_url, href)))
+}
+
 fn prefix_href(base: &str, href: &str) -> String {
     if href.starts_with('#') {
         href.to_string()
@@ -379,7 +565,7 @@ impl LinkedSection {
         self.kind.get_style()
     }
 
-    fn get_href(&self, base: &BaseUrl) -> String {
+    pub fn get_href(&self, base: &BaseUrl) -> String {
         let kind_href = self.kind.get_kind_href();
         let prefix = self.link_prefix.as_deref().unwrap_or(&base.filled);
         prefix_href(prefix, &kind_href)
@@ -618,6 +804,26 @@ impl Function {
                         </div>
                     ))
                 }
+                {
+                    if self.overridden_by.is_empty() {
+                        None
+                    } else {
+                        Some(html!(
+                            <div class="info">
+                                <span class="info_icon">"ⓘ"</span>
+                                "overridden by "
+                                { self.overridden_by.iter().enumerate().map(|(i, o)| html!(
+                                    <span>
+                                        { if i > 0 { Some(text!(", ")) } else { None } }
+                                        <code><a href={ o.get_href(base) } class={ o.get_style() }>
+                                            { text!(&o.text) }
+                                        </a></code>
+                                    </span>
+                                )) }
+                            </div>
+                        ))
+                    }
+                }
                 { render_doc_comment(&self.doc_comment, false, &docs_id, item_provider, &self.context, base) }
                 <hr/>
             </div>
@@ -631,6 +837,28 @@ impl Constant {
         html!(
             <div>
                 <div class="doc_row" id={ Id::new(format!("constant.{}", self.name)) }>
+                    <div class="doc_main">
+                        { self.def.render(base) }
+                        { self.value.as_ref().map(|v| html!(
+                            <span class="evaluated_value">{ text!(format!("= {}", v)) }</span>
+                        )) }
+                    </div>
+                    { render_doc_vis_toggle_button(&self.doc_comment, &docs_id) }
+                </div>
+                { render_array_elements(&self.elements) }
+                { render_doc_comment(&self.doc_comment, false, &docs_id, item_provider, &self.context, base) }
+                <hr/>
+            </div>
+        )
+    }
+}
+
+impl Operator {
+    fn render(&self, item_provider: &ItemProvider, base: &BaseUrl) -> Box<dyn FlowContent<String>> {
+        let docs_id = format!("operator.{}.docs", self.id);
+        html!(
+            <div>
+                <div class="doc_row" id={ Id::new(format!("operator.{}", self.id)) }>
                     <div class="doc_main">
                         { self.def.render(base) }
                     </div>
@@ -643,6 +871,28 @@ impl Constant {
     }
 }
 
+/// The elements of a static const array as a collapsed table of their indices and values.
+fn render_array_elements(elements: &[ArrayElement]) -> Option<Box<dyn FlowContent<String>>> {
+    if elements.is_empty() {
+        return None;
+    }
+    Some(html!(
+        <details class="array_elements">
+            <summary>{ text!(format!("{} elements", elements.len())) }</summary>
+            <div class="array_grid">
+                <div class="array_grid_heading">"Index"</div>
+                <div class="array_grid_heading">"Element"</div>
+                <div class="array_grid_heading">"Value"</div>
+                { elements.iter().enumerate().flat_map(|(i, e)| [
+                    html!(<div>{ text!(i.to_string()) }</div>) as Box<dyn FlowContent<String>>,
+                    html!(<div><code>{ text!(&e.expr) }</code></div>) as _,
+                    html!(<div>{ text!(e.value.as_deref().unwrap_or_default()) }</div>) as _,
+                ]) }
+            </div>
+        </details>
+    ))
+}
+
 impl Property {
     fn render(&self, item_provider: &ItemProvider, base: &BaseUrl) -> Box<dyn FlowContent<String>> {
         let docs_id = format!("property.{}.docs", self.name);
@@ -862,6 +1112,8 @@ fn version_selector(
 
 fn render_sidebar(
     data: SidebarData,
+    title: &str,
+    site: &SiteInfo,
     base: &BaseUrl,
     version_info: Option<&VersionInfo>,
 ) -> Box<dyn FlowContent<String>> {
@@ -889,6 +1141,20 @@ fn render_sidebar(
                 </p>
             </div>
             <hr/>
+            {
+                if !site.nav_links.is_empty() {
+                    Some(html!(
+                        <div>
+                            { site.nav_links.iter().map(|l| html!(
+                                <a class="sidebar_link sidebar_clickable" href={ l.url.as_str() } title={ l.text.as_str() }>
+                                    { text!(&l.text) }
+                                </a>
+                            )) }
+                            <hr/>
+                        </div>
+                    ))
+                } else { None }
+            }
             { data.sections.iter().map(|s| match s {
                 SidebarSection::Header { text, link: Some(link) } => html!(
                     <div>
@@ -910,6 +1176,11 @@ fn render_sidebar(
                     </a>
                 ) as Box<dyn FlowContent<_>>
             }) }
+            { site.templates.sidebar.as_ref().map(|t| html!(
+                <div id="sidebar_custom" class="sidebar_text">
+                    { unsafe_text!(fill_template(t, title, base, version_info)) }
+                </div>
+            )) }
         </nav>
     )
 }
@@ -921,6 +1192,7 @@ impl Class {
         item_provider: &ItemProvider,
         base: &BaseUrl,
         version_info: Option<&VersionInfo>,
+        site: &SiteInfo,
     ) -> DOMTree<String> {
         let sections =
             sidebar_sections_from_slice("Constants", "#constants", &self.constants, |v| {
@@ -965,6 +1237,15 @@ impl Class {
                     link: format!("#function.{}", v.name),
                 },
             ))
+            .chain(sidebar_sections_from_slice(
+                "Required Overrides",
+                "#required_overrides",
+                &self.required_overrides,
+                |v| SidebarSection::Text {
+                    text: v.text.clone(),
+                    link: v.get_href(base),
+                },
+            ))
             .chain(sidebar_sections_from_slice(
                 "Inner Structs",
                 "#inner_structs",
@@ -1109,6 +1390,19 @@ impl Class {
                                     v.render(item_provider, base)
                                 }
                             )
+                        ).chain(
+                            render_section_from_slice(
+                                "Required Overrides", "required_overrides", "", &self.required_overrides, false,
+                                |o| {
+                                    [html!(
+                                        <div class="doc_row">
+                                            <code><a href={ o.get_href(base) } class={ o.get_style() }>
+                                                { text!(&o.text) }
+                                            </a></code>
+                                        </div>
+                                    ) as Box<dyn FlowContent<String>>]
+                                }
+                            )
                         ).chain(
                             render_summary_grid(
                                 "Inner Structs",
@@ -1142,7 +1436,9 @@ impl Class {
                 </div>
             ),
             sidebar_data,
-            None,
+            &format!("/class.{}.html", self.name),
+            render_doc_summary_text(&self.doc_comment, item_provider, &self.context, base),
+            site,
             base,
             version_info,
         )
@@ -1156,6 +1452,7 @@ impl Struct {
         item_provider: &ItemProvider,
         base: &BaseUrl,
         version_info: Option<&VersionInfo>,
+        site: &SiteInfo,
     ) -> DOMTree<String> {
         let sections =
             sidebar_sections_from_slice("Constants", "#constants", &self.constants, |v| {
@@ -1267,7 +1564,9 @@ impl Struct {
                 </div>
             ),
             sidebar_data,
-            None,
+            &format!("/struct.{}.html", self.name),
+            render_doc_summary_text(&self.doc_comment, item_provider, &self.context, base),
+            site,
             base,
             version_info,
         )
@@ -1281,6 +1580,7 @@ impl Builtin {
         item_provider: &ItemProvider,
         base: &BaseUrl,
         version_info: Option<&VersionInfo>,
+        site: &SiteInfo,
     ) -> DOMTree<String> {
         let sections =
             sidebar_sections_from_slice("Constants", "#constants", &self.constants, |v| {
@@ -1307,17 +1607,45 @@ impl Builtin {
                     link: format!("#member.{}", v.name),
                 },
             ))
+            .chain(sidebar_sections_from_slice(
+                "Operators",
+                "#operators",
+                &self.operators,
+                |v| SidebarSection::Text {
+                    text: v.name.clone(),
+                    link: format!("#operator.{}", v.id),
+                },
+            ))
+            .chain(sidebar_sections_from_slice(
+                "Inner Structs",
+                "#inner_structs",
+                &self.inner_structs,
+                |v| SidebarSection::Text {
+                    text: v.no_context_name.clone(),
+                    link: format!("#builtin.{}", v.name),
+                },
+            ))
+            .chain(sidebar_sections_from_slice(
+                "Inner Enums",
+                "#inner_enums",
+                &self.inner_enums,
+                |v| SidebarSection::Text {
+                    text: v.no_context_name.clone(),
+                    link: format!("#enum.{}", v.name),
+                },
+            ))
             .collect_vec();
         let sidebar_data = SidebarData {
             docs_name: docs_name.to_string(),
-            title: format!("Builtin {}", self.name),
+            title: format!("Builtin {}{}", self.name, self.type_params_text()),
             sections,
         };
         let docs_id = format!("builtin.{}.docs", self.name);
         render_html_boilerplate(
             &format!(
-                "Builtin {} - {}",
+                "Builtin {}{} - {}",
                 self.name,
+                self.type_params_text(),
                 title_name(docs_name, version_info)
             ),
             html!(
@@ -1332,6 +1660,7 @@ impl Builtin {
                                 >
                                     { text!(add_zws(&self.name)) }
                                 </a>
+                                { text!(self.type_params_text()) }
                             </h1>
                         </div>
                         { render_doc_vis_toggle_button(&self.doc_comment, &docs_id) }
@@ -1358,12 +1687,49 @@ impl Builtin {
                                     v.render(item_provider, base)
                                 }
                             )
+                        ).chain(
+                            render_section_from_slice(
+                                "Operators", "operators", "", &self.operators, false,
+                                |v| {
+                                    v.render(item_provider, base)
+                                }
+                            )
+                        ).chain(
+                            render_summary_grid(
+                                "Inner Structs",
+                                "inner_structs",
+                                "builtin",
+                                &self.inner_structs.iter().map(|s| SummaryGridRow {
+                                    name: s.name.clone(),
+                                    link: format!("/builtin.{}.html", s.name),
+                                    doc_comment: s.doc_comment.clone(),
+                                    context: &s.context,
+                                }).collect_vec(),
+                                item_provider,
+                                base,
+                            )
+                        ).chain(
+                            render_summary_grid(
+                                "Inner Enums",
+                                "inner_enums",
+                                "enum",
+                                &self.inner_enums.iter().map(|e| SummaryGridRow {
+                                    name: e.name.clone(),
+                                    link: format!("/enum.{}.html", e.name),
+                                    doc_comment: e.doc_comment.clone(),
+                                    context: &e.context,
+                                }).collect_vec(),
+                                item_provider,
+                                base,
+                            )
                         )
                     }
                 </div>
             ),
             sidebar_data,
-            None,
+            &format!("/builtin.{}.html", self.name),
+            render_doc_summary_text(&self.doc_comment, item_provider, &self.context, base),
+            site,
             base,
             version_info,
         )
@@ -1379,6 +1745,11 @@ impl Enumerator {
                     <div class="doc_main">
                         <div class="doc_row" id={ Id::new(format!("enumerator.{}", self.name)) }>
                             { self.decl.render(base) }
+                            { self.value.map(|v| html!(
+                                <span class="evaluated_value">
+                                    { text!(format!("{} ({})", v, crate::consteval::int_hex(v))) }
+                                </span>
+                            )) }
                         </div>
                     </div>
                     { render_doc_vis_toggle_button(&self.doc_comment, &docs_id) }
@@ -1390,6 +1761,33 @@ impl Enumerator {
     }
 }
 
+/// A table of the bit each enumerator of a flags enum sets.
+fn render_flags_grid(enumerators: &[Enumerator]) -> Box<dyn FlowContent<String>> {
+    html!(
+        <div>
+            <h1 class="sub_heading" id="flags">"Flags"</h1>
+            <div class="flags_grid">
+                <div class="flags_grid_heading">"Name"</div>
+                <div class="flags_grid_heading">"Bit"</div>
+                <div class="flags_grid_heading">"Value"</div>
+                { enumerators.iter().filter_map(|e| Some((e, e.value.filter(|v| *v != 0)?))).map(|(e, v)| [
+                    html!(
+                        <div>
+                            <code>
+                                <a href={ format!("#enumerator.{}", e.name) } class="constant">
+                                    { text!(add_zws(&e.name)) }
+                                </a>
+                            </code>
+                        </div>
+                    ) as Box<dyn FlowContent<String>>,
+                    html!(<div>{ text!(v.trailing_zeros().to_string()) }</div>) as _,
+                    html!(<div><code>{ text!(crate::consteval::int_hex(v)) }</code></div>) as _,
+                ]).flatten() }
+            </div>
+        </div>
+    )
+}
+
 impl Enum {
     pub fn render(
         &self,
@@ -1397,6 +1795,7 @@ impl Enum {
         item_provider: &ItemProvider,
         base: &BaseUrl,
         version_info: Option<&VersionInfo>,
+        site: &SiteInfo,
     ) -> DOMTree<String> {
         let sections =
             sidebar_sections_from_slice("Enumerators", "#enumerators", &self.enumerators, |v| {
@@ -1432,12 +1831,15 @@ impl Enum {
                         { render_doc_vis_toggle_button(&self.doc_comment, &docs_id) }
                     </div>
                     { render_doc_comment(&self.doc_comment, true, &docs_id, item_provider, &self.context, base) }
+                    { if self.is_flags { Some(render_flags_grid(&self.enumerators)) } else { None } }
                     <h1 class="sub_heading" id="enumerators">"Enumerators"</h1>
                     { self.enumerators.iter().map(|v| v.render(item_provider, base)) }
                 </div>
             ),
             sidebar_data,
-            None,
+            &format!("/enum.{}.html", self.name),
+            render_doc_summary_text(&self.doc_comment, item_provider, &self.context, base),
+            site,
             base,
             version_info,
         )
@@ -1450,7 +1852,7 @@ impl Documentation {
         item_provider: &ItemProvider,
         base: &BaseUrl,
         version_info: Option<&VersionInfo>,
-        canonical_domain: Option<&str>,
+        site: &SiteInfo,
     ) -> DOMTree<String> {
         let mut sections = vec![SidebarSection::Header {
             text: "Contents".to_string(),
@@ -1598,9 +2000,9 @@ impl Documentation {
                 </div>
             ),
             sidebar_data,
-            canonical_domain
-                .map(|d| d.to_string() + &base.filled + "/index.html")
-                .as_deref(),
+            "/index.html",
+            render_doc_summary_text(&self.summary_doc, item_provider, &[], base),
+            site,
             base,
             version_info,
         )
@@ -1615,6 +2017,7 @@ pub fn render_from_markdown(
     item_provider: &ItemProvider,
     base: &BaseUrl,
     version_info: Option<&VersionInfo>,
+    site: &SiteInfo,
 ) -> DOMTree<String> {
     let sections = vec![];
     let sidebar_data = SidebarData {
@@ -1638,7 +2041,9 @@ pub fn render_from_markdown(
             </div>
         ),
         sidebar_data,
-        None,
+        &format!("/{}", link),
+        render_doc_summary_text(markdown, item_provider, &[], base),
+        site,
         base,
         version_info,
     )
@@ -1,9 +1,17 @@
+use std::collections::{BTreeMap, HashMap};
+
 use itertools::Itertools;
 use serde::Serialize;
+use zscript_parser::interner::NameSymbol;
 
-use crate::{item::ItemProvider, render::render_doc_summary, structures::*};
+use crate::{
+    item::ItemProvider,
+    render::{heading_slugs, render_doc_summary},
+    structures::*,
+    MarkdownFileToRender,
+};
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone, Copy)]
 pub enum SearchResultKind {
     Class,
     Struct,
@@ -13,27 +21,56 @@ pub enum SearchResultKind {
     Member,
     Constant,
     Enumerator,
+    Property,
+    Flag,
     Global,
+    Page,
 }
 
+/// One entry of `search.json`, serialized as the array `[name_prelude, name, link, kind, archive]`,
+/// where `archive` is an index into `SearchIndex::archives`.
 #[derive(Serialize)]
-pub struct SearchResult {
-    name_prelude: String,
-    name: String,
-    link: String,
-    desc: String,
-    kind: SearchResultKind,
-}
+pub struct SearchResult(String, String, String, SearchResultKind, usize);
 
+/// The contents of `search.json`, which is all the front-end needs to search by name.
+///
+/// The rendered description of the entry at index `i` lives in the description shard
+/// `search/desc.{i / shard_size}.json`, at index `i % shard_size`. Those shards are JSON arrays of
+/// HTML strings, and are only fetched when a result from them is shown. In offline builds the
+/// shards are instead scripts named `search/desc.{n}.js`, which assign the array to
+/// `window.zscdoc_search_shards[n]`.
+///
+/// `archives` holds the names of the archives the results come from. The first is always the
+/// documented archive itself; any others are dependencies, whose results link to absolute URLs.
 #[derive(Serialize)]
-pub struct SearchResults {
+pub struct SearchIndex {
+    shard_size: usize,
+    archives: Vec<String>,
     results: Vec<SearchResult>,
 }
 
+pub struct SearchResults {
+    pub index: SearchIndex,
+    pub description_shards: Vec<Vec<String>>,
+}
+
+const SHARD_SIZE: usize = 512;
+
+/// A documented item, collected once and then turned into both the name search results and the
+/// full-text index. The language server also uses these for its workspace symbols.
+pub struct SearchItem<'a> {
+    pub name_prelude: String,
+    pub name: String,
+    pub link: String,
+    pub doc_comment: &'a str,
+    pub context: &'a [NameSymbol],
+    pub kind: SearchResultKind,
+}
+
 fn summarize(
     doc_comment: &str,
     item_provider: &ItemProvider,
-    context: &[zscript_parser::interner::NameSymbol],
+    context: &[NameSymbol],
     base: &BaseUrl,
 ) -> String {
     render_doc_summary(doc_comment, item_provider, context, base)
@@ -41,220 +78,472 @@ fn summarize(
         .unwrap_or_default()
 }
 
-fn collect_class(c: &Class, res: &mut SearchResults, item_provider: &ItemProvider, base: &BaseUrl) {
-    res.results.push(SearchResult {
+fn split_name(name: &str) -> (String, String) {
+    let split = name.split('.').collect_vec();
+    let (last, prelude) = split.split_last().unwrap();
+    let name_prelude = if prelude.is_empty() {
+        "".to_string()
+    } else {
+        format!("{}.", prelude.join("."))
+    };
+    (name_prelude, last.to_string())
+}
+
+fn collect_class<'a>(c: &'a Class, res: &mut Vec<SearchItem<'a>>) {
+    res.push(SearchItem {
         name_prelude: "".to_string(),
         name: c.name.to_string(),
         link: format!("class.{}.html", c.name),
-        desc: summarize(&c.doc_comment, item_provider, &c.context, base),
+        doc_comment: &c.doc_comment,
+        context: &c.context,
         kind: SearchResultKind::Class,
     });
     for co in c.constants.iter() {
-        res.results.push(SearchResult {
+        res.push(SearchItem {
             name_prelude: format!("{}.", c.name),
             name: co.name.to_string(),
             link: format!("class.{}.html#constant.{}", c.name, co.name),
-            desc: summarize(&co.doc_comment, item_provider, &co.context, base),
+            doc_comment: &co.doc_comment,
+            context: &co.context,
             kind: SearchResultKind::Constant,
         });
     }
     for mf in [&c.public, &c.protected] {
         for f in mf.functions.iter() {
-            res.results.push(SearchResult {
+            res.push(SearchItem {
                 name_prelude: format!("{}.", c.name),
                 name: f.name.to_string(),
                 link: format!("class.{}.html#function.{}", c.name, f.name),
-                desc: summarize(&f.doc_comment, item_provider, &f.context, base),
+                doc_comment: &f.doc_comment,
+                context: &f.context,
                 kind: SearchResultKind::Function,
             });
         }
         for m in mf.variables.iter() {
-            res.results.push(SearchResult {
+            res.push(SearchItem {
                 name_prelude: format!("{}.", c.name),
                 name: m.name.to_string(),
                 link: format!("class.{}.html#member.{}", c.name, m.name),
-                desc: summarize(&m.doc_comment, item_provider, &m.context, base),
+                doc_comment: &m.doc_comment,
+                context: &m.context,
                 kind: SearchResultKind::Member,
             });
         }
     }
+    for p in c.properties.iter() {
+        res.push(SearchItem {
+            name_prelude: format!("{}.", c.name),
+            name: p.name.to_string(),
+            link: format!("class.{}.html#property.{}", c.name, p.name),
+            doc_comment: &p.doc_comment,
+            context: &p.context,
+            kind: SearchResultKind::Property,
+        });
+    }
+    for f in c.flags.iter() {
+        res.push(SearchItem {
+            name_prelude: format!("{}.", c.name),
+            name: f.name.to_string(),
+            link: format!("class.{}.html#flag.{}", c.name, f.name),
+            doc_comment: &f.doc_comment,
+            context: &f.context,
+            kind: SearchResultKind::Flag,
+        });
+    }
     for s in c.inner_structs.iter() {
-        collect_struct(s, res, item_provider, base);
+        collect_struct(s, res);
+    }
+    for e in c.inner_enums.iter() {
+        collect_enum(e, res);
     }
 }
 
-fn collect_struct(
-    s: &Struct,
-    res: &mut SearchResults,
-    item_provider: &ItemProvider,
-    base: &BaseUrl,
-) {
-    let split = s.name.split('.').collect_vec();
-    let (last, prelude) = split.split_last().unwrap();
-    let name_prelude = if prelude.is_empty() {
-        "".to_string()
-    } else {
-        format!("{}.", prelude.join("."))
-    };
-    let name = last.to_string();
-
-    res.results.push(SearchResult {
+fn collect_struct<'a>(s: &'a Struct, res: &mut Vec<SearchItem<'a>>) {
+    let (name_prelude, name) = split_name(&s.name);
+    res.push(SearchItem {
         name_prelude,
         name,
         link: format!("struct.{}.html", s.name),
-        desc: summarize(&s.doc_comment, item_provider, &s.context, base),
+        doc_comment: &s.doc_comment,
+        context: &s.context,
         kind: SearchResultKind::Struct,
     });
     for co in s.constants.iter() {
-        res.results.push(SearchResult {
+        res.push(SearchItem {
             name_prelude: format!("{}.", s.name),
             name: co.name.to_string(),
             link: format!("struct.{}.html#constant.{}", s.name, co.name),
-            desc: summarize(&co.doc_comment, item_provider, &co.context, base),
+            doc_comment: &co.doc_comment,
+            context: &co.context,
             kind: SearchResultKind::Constant,
         });
     }
     for mf in [&s.public, &s.protected] {
         for f in mf.functions.iter() {
-            res.results.push(SearchResult {
+            res.push(SearchItem {
                 name_prelude: format!("{}.", s.name),
                 name: f.name.to_string(),
                 link: format!("struct.{}.html#function.{}", s.name, f.name),
-                desc: summarize(&f.doc_comment, item_provider, &f.context, base),
+                doc_comment: &f.doc_comment,
+                context: &f.context,
                 kind: SearchResultKind::Function,
             });
         }
         for m in mf.variables.iter() {
-            res.results.push(SearchResult {
+            res.push(SearchItem {
                 name_prelude: format!("{}.", s.name),
                 name: m.name.to_string(),
                 link: format!("struct.{}.html#member.{}", s.name, m.name),
-                desc: summarize(&m.doc_comment, item_provider, &m.context, base),
+                doc_comment: &m.doc_comment,
+                context: &m.context,
                 kind: SearchResultKind::Member,
             });
         }
     }
     for e in s.inner_enums.iter() {
-        collect_enum(e, res, item_provider, base);
+        collect_enum(e, res);
     }
 }
 
-fn collect_builtin(
-    b: &Builtin,
-    res: &mut SearchResults,
-    item_provider: &ItemProvider,
-    base: &BaseUrl,
-) {
-    let split = b.name.split('.').collect_vec();
-    let (last, prelude) = split.split_last().unwrap();
-    let name_prelude = if prelude.is_empty() {
-        "".to_string()
-    } else {
-        format!("{}.", prelude.join("."))
-    };
-    let name = last.to_string();
-
-    res.results.push(SearchResult {
+fn collect_builtin<'a>(b: &'a Builtin, res: &mut Vec<SearchItem<'a>>) {
+    let (name_prelude, name) = split_name(&b.name);
+    res.push(SearchItem {
         name_prelude,
         name,
         link: format!("builtin.{}.html", b.name),
-        desc: summarize(&b.doc_comment, item_provider, &b.context, base),
+        doc_comment: &b.doc_comment,
+        context: &b.context,
         kind: SearchResultKind::Builtin,
     });
     for co in b.constants.iter() {
-        res.results.push(SearchResult {
+        res.push(SearchItem {
             name_prelude: format!("{}.", b.name),
             name: co.name.to_string(),
             link: format!("builtin.{}.html#constant.{}", b.name, co.name),
-            desc: summarize(&co.doc_comment, item_provider, &co.context, base),
+            doc_comment: &co.doc_comment,
+            context: &co.context,
             kind: SearchResultKind::Constant,
         });
     }
     for f in b.functions.iter() {
-        res.results.push(SearchResult {
+        res.push(SearchItem {
             name_prelude: format!("{}.", b.name),
             name: f.name.to_string(),
-            link: format!("builtin.{}.html#function.{}", b.name, b.name),
-            desc: summarize(&f.doc_comment, item_provider, &f.context, base),
+            link: format!("builtin.{}.html#function.{}", b.name, f.name),
+            doc_comment: &f.doc_comment,
+            context: &f.context,
             kind: SearchResultKind::Function,
         });
     }
     for m in b.variables.iter() {
-        res.results.push(SearchResult {
+        res.push(SearchItem {
             name_prelude: format!("{}.", b.name),
             name: m.name.to_string(),
             link: format!("builtin.{}.html#member.{}", b.name, m.name),
-            desc: summarize(&m.doc_comment, item_provider, &m.context, base),
+            doc_comment: &m.doc_comment,
+            context: &m.context,
             kind: SearchResultKind::Member,
         });
     }
+    for s in b.inner_structs.iter() {
+        collect_builtin(s, res);
+    }
+    for e in b.inner_enums.iter() {
+        collect_enum(e, res);
+    }
 }
 
-fn collect_enum(e: &Enum, res: &mut SearchResults, item_provider: &ItemProvider, base: &BaseUrl) {
-    let split = e.name.split('.').collect_vec();
-    let (last, prelude) = split.split_last().unwrap();
-    let name_prelude = if prelude.is_empty() {
-        "".to_string()
-    } else {
-        format!("{}.", prelude.join("."))
-    };
-    let name = last.to_string();
-
-    res.results.push(SearchResult {
+fn collect_enum<'a>(e: &'a Enum, res: &mut Vec<SearchItem<'a>>) {
+    let (name_prelude, name) = split_name(&e.name);
+    res.push(SearchItem {
         name_prelude,
         name,
         link: format!("enum.{}.html", e.name),
-        desc: summarize(&e.doc_comment, item_provider, &e.context, base),
+        doc_comment: &e.doc_comment,
+        context: &e.context,
         kind: SearchResultKind::Enum,
     });
     for en in e.enumerators.iter() {
-        res.results.push(SearchResult {
+        res.push(SearchItem {
             name_prelude: format!("{}.", e.name),
             name: en.name.to_string(),
             link: format!("enum.{}.html#enumerator.{}", e.name, en.name),
-            desc: summarize(&en.doc_comment, item_provider, &en.context, base),
+            doc_comment: &en.doc_comment,
+            context: &en.context,
             kind: SearchResultKind::Enumerator,
         });
     }
 }
 
-pub fn collect_search_results(
-    docs: &Documentation,
-    item_provider: &ItemProvider,
-    base: &BaseUrl,
-) -> SearchResults {
-    let mut res = SearchResults { results: vec![] };
+pub fn collect_items(docs: &Documentation) -> Vec<SearchItem<'_>> {
+    let mut res = vec![];
     for c in docs.constants.iter() {
-        res.results.push(SearchResult {
+        res.push(SearchItem {
             name_prelude: "".to_string(),
             name: c.name.to_string(),
             link: format!("index.html#constant.{}", c.name),
-            desc: summarize(&c.doc_comment, item_provider, &c.context, base),
+            doc_comment: &c.doc_comment,
+            context: &c.context,
             kind: SearchResultKind::Constant,
         });
     }
     for c in docs.classes.iter() {
-        collect_class(c, &mut res, item_provider, base);
+        collect_class(c, &mut res);
     }
     for s in docs.structs.iter() {
-        collect_struct(s, &mut res, item_provider, base);
+        collect_struct(s, &mut res);
     }
     for e in docs.enums.iter() {
-        collect_enum(e, &mut res, item_provider, base);
+        collect_enum(e, &mut res);
     }
     for b in docs.builtins.iter() {
-        collect_builtin(b, &mut res, item_provider, base);
+        collect_builtin(b, &mut res);
     }
     if let Some(g) = &docs.globals {
         for m in &g.variables {
-            res.results.push(SearchResult {
+            res.push(SearchItem {
                 name_prelude: "".to_string(),
                 name: m.name.to_string(),
                 link: format!("index.html#global.{}", m.name),
-                desc: summarize(&m.doc_comment, item_provider, &m.context, base),
+                doc_comment: &m.doc_comment,
+                context: &m.context,
                 kind: SearchResultKind::Global,
             });
         }
     }
     res
 }
+
+pub fn collect_search_results(
+    docs: &Documentation,
+    dependency_docs: &[DependencyDocumentation],
+    item_provider: &ItemProvider,
+    base: &BaseUrl,
+) -> SearchResults {
+    let archives = std::iter::once(docs.name.clone())
+        .chain(dependency_docs.iter().map(|d| d.docs.name.clone()))
+        .collect_vec();
+    let main_items = collect_items(docs).into_iter().map(|i| (0, i));
+    let dependency_items = dependency_docs.iter().enumerate().flat_map(|(n, d)| {
+        collect_items(&d.docs).into_iter().map(move |mut i| {
+            i.link = format!("{}/{}", d.link.trim_end_matches('/'), i.link);
+            (n + 1, i)
+        })
+    });
+    let (results, descriptions): (Vec<_>, Vec<_>) = main_items
+        .chain(dependency_items)
+        .map(|(archive, i)| {
+            (
+                SearchResult(i.name_prelude, i.name, i.link, i.kind, archive),
+                summarize(i.doc_comment, item_provider, i.context, base),
+            )
+        })
+        .unzip();
+    SearchResults {
+        index: SearchIndex {
+            shard_size: SHARD_SIZE,
+            archives,
+            results,
+        },
+        description_shards: descriptions
+            .chunks(SHARD_SIZE)
+            .map(|c| c.to_vec())
+            .collect_vec(),
+    }
+}
+
+#[derive(Serialize)]
+pub struct FullTextDocument {
+    name_prelude: String,
+    name: String,
+    link: String,
+    snippet: String,
+    kind: SearchResultKind,
+}
+
+/// An inverted index over the prose of the documentation.
+///
+/// `terms` maps each lowercased word to the documents it appears in, as pairs of an index into
+/// `documents` and the number of times it appears there.
+#[derive(Serialize)]
+pub struct FullTextIndex {
+    documents: Vec<FullTextDocument>,
+    terms: BTreeMap<String, Vec<(usize, usize)>>,
+}
+
+const SNIPPET_LENGTH: usize = 150;
+
+const STOP_WORDS: [&str; 24] = [
+    "a", "an", "and", "are", "as", "at", "be", "by", "for", "from", "if", "in", "is", "it", "of",
+    "on", "or", "so", "that", "the", "this", "to", "was", "with",
+];
+
+fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|w| w.len() > 1)
+        .map(|w| w.to_lowercase())
+        .filter(|w| !STOP_WORDS.contains(&w.as_str()))
+}
+
+fn snippet(text: &str) -> String {
+    let text = text.split_whitespace().join(" ");
+    if text.chars().count() > SNIPPET_LENGTH {
+        format!("{}…", text.chars().take(SNIPPET_LENGTH).collect::<String>())
+    } else {
+        text
+    }
+}
+
+/// A chunk of markdown turned into plain text, split at each heading.
+struct MarkdownSection {
+    heading: Option<String>,
+    text: String,
+}
+
+fn markdown_sections(markdown: &str) -> Vec<MarkdownSection> {
+    use pulldown_cmark::{Event, Options, Parser, Tag};
+
+    let dedented = textwrap::dedent(markdown);
+    let mut sections = vec![MarkdownSection {
+        heading: None,
+        text: String::new(),
+    }];
+    let mut in_heading = false;
+    for event in Parser::new_ext(&dedented, Options::ENABLE_TABLES) {
+        match event {
+            Event::Start(Tag::Heading(..)) => {
+                in_heading = true;
+                sections.push(MarkdownSection {
+                    heading: Some(String::new()),
+                    text: String::new(),
+                });
+            }
+            Event::End(Tag::Heading(..)) => {
+                in_heading = false;
+            }
+            Event::Text(t) | Event::Code(t) | Event::Html(t) => {
+                let section = sections.last_mut().unwrap();
+                if in_heading {
+                    *section.heading.as_mut().unwrap() += &t;
+                } else {
+                    section.text += &t;
+                }
+            }
+            Event::SoftBreak | Event::HardBreak | Event::End(_) => {
+                let section = sections.last_mut().unwrap();
+                if !in_heading {
+                    section.text += " ";
+                }
+            }
+            _ => {}
+        }
+    }
+    sections
+}
+
+#[derive(Default)]
+struct FullTextIndexBuilder {
+    documents: Vec<FullTextDocument>,
+    terms: HashMap<String, Vec<(usize, usize)>>,
+}
+
+impl FullTextIndexBuilder {
+    fn add(&mut self, document: FullTextDocument, text: &str) {
+        let counts = tokenize(text).counts();
+        if counts.is_empty() {
+            return;
+        }
+        let doc_index = self.documents.len();
+        self.documents.push(document);
+        for (term, count) in counts {
+            self.terms.entry(term).or_default().push((doc_index, count));
+        }
+    }
+
+    fn finish(self) -> FullTextIndex {
+        FullTextIndex {
+            documents: self.documents,
+            terms: self.terms.into_iter().collect(),
+        }
+    }
+}
+
+fn add_markdown_page(
+    builder: &mut FullTextIndexBuilder,
+    title: &str,
+    output_filename: &str,
+    doc_id: &str,
+    markdown: &str,
+) {
+    let sections = markdown_sections(markdown);
+    let slugs = heading_slugs(markdown, doc_id);
+    let mut slugs = slugs.iter();
+    for s in sections {
+        let (name, link, text) = match &s.heading {
+            Some(h) => (
+                h.trim().to_string(),
+                format!("{}#{}", output_filename, slugs.next().unwrap()),
+                format!("{} {}", h, s.text),
+            ),
+            None => (
+                title.to_string(),
+                output_filename.to_string(),
+                format!("{} {}", title, s.text),
+            ),
+        };
+        builder.add(
+            FullTextDocument {
+                name_prelude: if s.heading.is_some() {
+                    format!("{} › ", title)
+                } else {
+                    "".to_string()
+                },
+                name,
+                link,
+                snippet: snippet(&s.text),
+                kind: SearchResultKind::Page,
+            },
+            &text,
+        );
+    }
+}
+
+pub fn collect_full_text_index(
+    docs: &Documentation,
+    markdown_files: &[MarkdownFileToRender],
+) -> FullTextIndex {
+    let mut builder = FullTextIndexBuilder::default();
+    add_markdown_page(
+        &mut builder,
+        &docs.name,
+        "index.html",
+        "summary_doc",
+        &docs.summary_doc,
+    );
+    for m in markdown_files {
+        add_markdown_page(
+            &mut builder,
+            &m.title,
+            &m.output_filename,
+            "content",
+            &m.markdown,
+        );
+    }
+    for i in collect_items(docs) {
+        let text = markdown_sections(i.doc_comment)
+            .into_iter()
+            .flat_map(|s| s.heading.into_iter().chain(Some(s.text)))
+            .join(" ");
+        builder.add(
+            FullTextDocument {
+                name_prelude: i.name_prelude,
+                name: i.name,
+                link: i.link,
+                snippet: snippet(&text),
+                kind: i.kind,
+            },
+            &text,
+        );
+    }
+    builder.finish()
+}
@@ -0,0 +1,711 @@
+use std::{collections::HashMap, io::IsTerminal};
+
+use crossterm::style::{Attribute, Color, ContentStyle};
+use pulldown_cmark::{BrokenLink, CowStr, Event, LinkType, Options, Parser, Tag};
+use zscript_parser::{
+    interner::{intern_name, NameSymbol},
+    Span,
+};
+
+use crate::{item::ItemProvider, structures::*, wiki::source_text};
+
+/// Everything `zscdoc show` prints about an item, which the language server also uses.
+pub struct ShownItem<'a> {
+    pub signature: String,
+    pub doc_comment: &'a str,
+    pub context: &'a [NameSymbol],
+    pub deprecated: Option<&'a Deprecated>,
+    /// Where the item is defined, which builtin types don't have.
+    pub span: Option<Span>,
+    /// For types, the names of the things inside them, grouped under a heading.
+    children: Vec<(&'static str, Vec<&'a str>)>,
+}
+
+impl<'a> ShownItem<'a> {
+    fn leaf(
+        signature: &SourceCodeWithLinks,
+        doc_comment: &'a str,
+        context: &'a [NameSymbol],
+        span: Span,
+    ) -> Self {
+        Self {
+            signature: source_text(signature),
+            doc_comment,
+            context,
+            deprecated: None,
+            span: Some(span),
+            children: vec![],
+        }
+    }
+}
+
+fn constant(c: &Constant) -> ShownItem<'_> {
+    let mut item = ShownItem::leaf(&c.def, &c.doc_comment, &c.context, c.span);
+    // the HTML docs list a static const array's elements separately, so they aren't in `def`
+    if !c.elements.is_empty() {
+        let elements: Vec<_> = c
+            .elements
+            .iter()
+            .map(|e| format!("    {}", e.expr))
+            .collect();
+        item.signature += &format!(" = {{\n{}\n}}", elements.join(",\n"));
+    }
+    item
+}
+
+fn variable(v: &MemberVariable) -> ShownItem<'_> {
+    ShownItem {
+        deprecated: v.deprecated.as_ref(),
+        ..ShownItem::leaf(&v.def, &v.doc_comment, &v.context, v.span)
+    }
+}
+
+fn function(f: &Function) -> ShownItem<'_> {
+    ShownItem {
+        deprecated: f.deprecated.as_ref(),
+        ..ShownItem::leaf(&f.signature, &f.doc_comment, &f.context, f.span)
+    }
+}
+
+fn find_variable<'a>(groups: [&'a VariablesAndFunctions; 3], name: &str) -> Option<ShownItem<'a>> {
+    groups
+        .into_iter()
+        .flat_map(|g| g.variables.iter())
+        .find(|v| v.name == name)
+        .map(variable)
+}
+
+fn find_function<'a>(groups: [&'a VariablesAndFunctions; 3], name: &str) -> Option<ShownItem<'a>> {
+    groups
+        .into_iter()
+        .flat_map(|g| g.functions.iter())
+        .find(|f| f.name == name)
+        .map(function)
+}
+
+fn variable_names<'a>(groups: [&'a VariablesAndFunctions; 2]) -> Vec<&'a str> {
+    groups
+        .into_iter()
+        .flat_map(|g| g.variables.iter())
+        .map(|v| v.name.as_str())
+        .collect()
+}
+
+fn function_names<'a>(groups: [&'a VariablesAndFunctions; 2]) -> Vec<&'a str> {
+    groups
+        .into_iter()
+        .flat_map(|g| g.functions.iter())
+        .map(|f| f.name.as_str())
+        .collect()
+}
+
+fn with_flags(mut signature: String, flags: &SourceCodeWithLinks) -> String {
+    let flags = source_text(flags);
+    if !flags.is_empty() {
+        signature += &format!(" {}", flags);
+    }
+    signature
+}
+
+/// A page of the HTML documentation. Every item is either the subject of one of these or is
+/// documented on one.
+#[derive(Clone, Copy)]
+enum Page<'a> {
+    Index(&'a Documentation),
+    Class(&'a Class),
+    Struct(&'a Struct),
+    Enum(&'a Enum),
+    Builtin(&'a Builtin),
+}
+
+fn pages(docs: &Documentation) -> Vec<Page<'_>> {
+    let mut ret = vec![Page::Index(docs)];
+    for c in docs.classes.iter() {
+        ret.push(Page::Class(c));
+        for s in c.inner_structs.iter() {
+            ret.push(Page::Struct(s));
+            ret.extend(s.inner_enums.iter().map(Page::Enum));
+        }
+        ret.extend(c.inner_enums.iter().map(Page::Enum));
+    }
+    for s in docs.structs.iter() {
+        ret.push(Page::Struct(s));
+        ret.extend(s.inner_enums.iter().map(Page::Enum));
+    }
+    ret.extend(docs.enums.iter().map(Page::Enum));
+    for b in docs.builtins.iter().flat_map(|b| b.with_nested()) {
+        ret.push(Page::Builtin(b));
+        ret.extend(b.inner_enums.iter().map(Page::Enum));
+    }
+    ret
+}
+
+impl<'a> Page<'a> {
+    fn filename(&self) -> String {
+        match self {
+            Page::Index(_) => "index.html".to_string(),
+            Page::Class(c) => format!("class.{}.html", c.name),
+            Page::Struct(s) => format!("struct.{}.html", s.name),
+            Page::Enum(e) => format!("enum.{}.html", e.name),
+            Page::Builtin(b) => format!("builtin.{}.html", b.name),
+        }
+    }
+
+    /// The type this page documents.
+    fn item(&self) -> Option<ShownItem<'a>> {
+        match *self {
+            Page::Index(_) => None,
+            Page::Class(c) => {
+                let mut signature = format!("class {}", c.name);
+                if let Some(inherits) = &c.inherits {
+                    signature += &format!(" : {}", source_text(inherits));
+                }
+                let mut signature = with_flags(signature, &c.def_flags);
+                if let Some(sealed) = &c.sealed {
+                    signature += &format!(" sealed({})", source_text(sealed));
+                }
+                Some(ShownItem {
+                    signature,
+                    doc_comment: &c.doc_comment,
+                    context: &c.context,
+                    deprecated: c.deprecated.as_ref(),
+                    span: Some(c.span),
+                    children: vec![
+                        (
+                            "Constants",
+                            c.constants.iter().map(|x| x.name.as_str()).collect(),
+                        ),
+                        ("Members", variable_names([&c.public, &c.protected])),
+                        ("Functions", function_names([&c.public, &c.protected])),
+                        (
+                            "Properties",
+                            c.properties.iter().map(|x| x.name.as_str()).collect(),
+                        ),
+                        ("Flags", c.flags.iter().map(|x| x.name.as_str()).collect()),
+                        (
+                            "Structs",
+                            c.inner_structs
+                                .iter()
+                                .map(|x| x.no_context_name.as_str())
+                                .collect(),
+                        ),
+                        (
+                            "Enums",
+                            c.inner_enums
+                                .iter()
+                                .map(|x| x.no_context_name.as_str())
+                                .collect(),
+                        ),
+                    ],
+                })
+            }
+            Page::Struct(s) => Some(ShownItem {
+                signature: with_flags(format!("struct {}", s.name), &s.def_flags),
+                doc_comment: &s.doc_comment,
+                context: &s.context,
+                deprecated: s.deprecated.as_ref(),
+                span: Some(s.span),
+                children: vec![
+                    (
+                        "Constants",
+                        s.constants.iter().map(|x| x.name.as_str()).collect(),
+                    ),
+                    ("Members", variable_names([&s.public, &s.protected])),
+                    ("Functions", function_names([&s.public, &s.protected])),
+                    (
+                        "Enums",
+                        s.inner_enums
+                            .iter()
+                            .map(|x| x.no_context_name.as_str())
+                            .collect(),
+                    ),
+                ],
+            }),
+            Page::Enum(e) => Some(ShownItem {
+                signature: format!("enum {}", e.name),
+                doc_comment: &e.doc_comment,
+                context: &e.context,
+                deprecated: None,
+                span: Some(e.span),
+                children: vec![(
+                    "Enumerators",
+                    e.enumerators.iter().map(|x| x.name.as_str()).collect(),
+                )],
+            }),
+            Page::Builtin(b) => Some(ShownItem {
+                signature: format!("builtin {}{}", b.name, b.type_params_text()),
+                doc_comment: &b.doc_comment,
+                context: &b.context,
+                deprecated: None,
+                span: None,
+                children: vec![
+                    (
+                        "Constants",
+                        b.constants.iter().map(|x| x.name.as_str()).collect(),
+                    ),
+                    (
+                        "Members",
+                        b.variables.iter().map(|x| x.name.as_str()).collect(),
+                    ),
+                    (
+                        "Functions",
+                        b.functions.iter().map(|x| x.name.as_str()).collect(),
+                    ),
+                    (
+                        "Operators",
+                        b.operators.iter().map(|x| x.name.as_str()).collect(),
+                    ),
+                    (
+                        "Structs",
+                        b.inner_structs
+                            .iter()
+                            .map(|x| x.no_context_name.as_str())
+                            .collect(),
+                    ),
+                    (
+                        "Enums",
+                        b.inner_enums
+                            .iter()
+                            .map(|x| x.no_context_name.as_str())
+                            .collect(),
+                    ),
+                ],
+            }),
+        }
+    }
+
+    /// The item documented at `anchor` on this page.
+    fn member(&self, anchor: &str) -> Option<ShownItem<'a>> {
+        let (kind, name) = anchor.split_once('.')?;
+        match (*self, kind) {
+            (Page::Index(d), "constant") => {
+                d.constants.iter().find(|x| x.name == name).map(constant)
+            }
+            (Page::Index(d), "global") => d
+                .globals
+                .as_ref()?
+                .variables
+                .iter()
+                .find(|x| x.name == name)
+                .map(variable),
+            (Page::Class(c), "constant") => {
+                c.constants.iter().find(|x| x.name == name).map(constant)
+            }
+            (Page::Class(c), "member") => {
+                find_variable([&c.public, &c.protected, &c.private], name)
+            }
+            (Page::Class(c), "function") => {
+                find_function([&c.public, &c.protected, &c.private], name)
+            }
+            (Page::Class(c), "property") => c
+                .properties
+                .iter()
+                .find(|x| x.name == name)
+                .map(|x| ShownItem::leaf(&x.def, &x.doc_comment, &x.context, x.span)),
+            (Page::Class(c), "flag") => c
+                .flags
+                .iter()
+                .find(|x| x.name == name)
+                .map(|x| ShownItem::leaf(&x.def, &x.doc_comment, &x.context, x.span)),
+            (Page::Struct(s), "constant") => {
+                s.constants.iter().find(|x| x.name == name).map(constant)
+            }
+            (Page::Struct(s), "member") => {
+                find_variable([&s.public, &s.protected, &s.private], name)
+            }
+            (Page::Struct(s), "function") => {
+                find_function([&s.public, &s.protected, &s.private], name)
+            }
+            (Page::Enum(e), "enumerator") => e
+                .enumerators
+                .iter()
+                .find(|x| x.name == name)
+                .map(|x| ShownItem::leaf(&x.decl, &x.doc_comment, &x.context, x.span)),
+            (Page::Builtin(b), "constant") => {
+                b.constants.iter().find(|x| x.name == name).map(constant)
+            }
+            (Page::Builtin(b), "member") => {
+                b.variables.iter().find(|x| x.name == name).map(variable)
+            }
+            (Page::Builtin(b), "function") => {
+                b.functions.iter().find(|x| x.name == name).map(function)
+            }
+            (Page::Builtin(b), "operator") => {
+                b.operators
+                    .iter()
+                    .find(|x| x.id == name)
+                    .map(|x| ShownItem {
+                        signature: source_text(&x.def),
+                        doc_comment: &x.doc_comment,
+                        context: &x.context,
+                        deprecated: None,
+                        span: None,
+                        children: vec![],
+                    })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Finds documented items by the link to them in the HTML documentation.
+pub struct ItemIndex<'a> {
+    /// Pages by the link prefix of their archive, which is `None` for the documented one, and
+    /// their filename, since every archive has its own `index.html` and so on.
+    pages: HashMap<(Option<String>, String), Page<'a>>,
+}
+
+impl<'a> ItemIndex<'a> {
+    /// Indexes the pages of each archive's documentation, given with the link prefix of the
+    /// archive.
+    pub fn new(docs: impl IntoIterator<Item = (Option<&'a str>, &'a Documentation)>) -> Self {
+        Self {
+            pages: docs
+                .into_iter()
+                .flat_map(|(link_prefix, d)| {
+                    pages(d)
+                        .into_iter()
+                        .map(move |p| ((link_prefix.map(str::to_string), p.filename()), p))
+                })
+                .collect(),
+        }
+    }
+
+    /// Finds the item at `href` in the archive with `link_prefix`. The href is relative to the
+    /// root of that archive's documentation, such as `class.Actor.html#function.Destroy`.
+    pub fn find(&self, link_prefix: Option<&str>, href: &str) -> Option<ShownItem<'a>> {
+        let href = href.trim_start_matches('/');
+        let (page, anchor) = match href.split_once('#') {
+            Some((page, anchor)) => (page, Some(anchor)),
+            None => (href, None),
+        };
+        let page = self
+            .pages
+            .get(&(link_prefix.map(str::to_string), page.to_string()))?;
+        match anchor {
+            Some(anchor) => page.member(anchor),
+            None => page.item(),
+        }
+    }
+}
+
+/// The documentation of the archive being documented and of each of its dependencies, with the
+/// link prefix of each, as [`ItemIndex::new`] takes them.
+pub fn archive_docs<'a>(
+    docs: &'a Documentation,
+    dependency_docs: &'a [DependencyDocumentation],
+) -> Vec<(Option<&'a str>, &'a Documentation)> {
+    std::iter::once((None, docs))
+        .chain(
+            dependency_docs
+                .iter()
+                .map(|d| (Some(d.link.as_str()), &d.docs)),
+        )
+        .collect()
+}
+
+fn paint(styled: bool, text: &str, foreground: Option<Color>, attributes: &[Attribute]) -> String {
+    if !styled || text.is_empty() {
+        return text.to_string();
+    }
+    let mut style = ContentStyle::new();
+    style.foreground_color = foreground;
+    for a in attributes {
+        style.attributes.set(*a);
+    }
+    style.apply(text).to_string()
+}
+
+/// Renders markdown doc comments as wrapped, styled text for a terminal.
+struct TerminalWriter<'a> {
+    item_provider: &'a ItemProvider,
+    styled: bool,
+    width: usize,
+    out: String,
+    /// The inline text of the block currently being written.
+    text: String,
+    /// What goes before each line of the current block, from enclosing block quotes and lists.
+    prefixes: Vec<String>,
+    /// The bullet of a list item whose first line hasn't been written yet.
+    bullet: Option<String>,
+    /// The next number of each enclosing list, or `None` for unordered ones.
+    lists: Vec<Option<u64>>,
+    cell: usize,
+    bold: usize,
+    italic: usize,
+    strikethrough: usize,
+    link: usize,
+    heading: bool,
+    code_block: bool,
+}
+
+impl<'a> TerminalWriter<'a> {
+    fn new(item_provider: &'a ItemProvider, styled: bool, width: usize) -> Self {
+        Self {
+            item_provider,
+            styled,
+            width,
+            out: String::new(),
+            text: String::new(),
+            prefixes: vec![],
+            bullet: None,
+            lists: vec![],
+            cell: 0,
+            bold: 0,
+            italic: 0,
+            strikethrough: 0,
+            link: 0,
+            heading: false,
+            code_block: false,
+        }
+    }
+
+    fn push_text(&mut self, text: &str) {
+        if self.code_block {
+            self.text += text;
+            return;
+        }
+        let mut attributes = vec![];
+        if self.bold > 0 || self.heading {
+            attributes.push(Attribute::Bold);
+        }
+        if self.italic > 0 {
+            attributes.push(Attribute::Italic);
+        }
+        if self.strikethrough > 0 {
+            attributes.push(Attribute::CrossedOut);
+        }
+        if self.link > 0 || self.heading {
+            attributes.push(Attribute::Underlined);
+        }
+        let foreground = (self.link > 0).then_some(Color::Blue);
+        self.text += &paint(self.styled, text, foreground, &attributes);
+    }
+
+    /// Wraps and writes out the current block's text.
+    fn flush(&mut self) {
+        let text = std::mem::take(&mut self.text);
+        if text.trim().is_empty() {
+            return;
+        }
+        let subsequent = self.prefixes.concat();
+        let initial = match self.bullet.take() {
+            Some(b) => self.prefixes[..self.prefixes.len() - 1].concat() + &b,
+            None => subsequent.clone(),
+        };
+        let options = textwrap::Options::new(self.width)
+            .initial_indent(&initial)
+            .subsequent_indent(&subsequent);
+        self.out += &textwrap::fill(text.trim(), options);
+        self.out += "\n";
+    }
+
+    fn blank_line(&mut self) {
+        if !self.out.is_empty() && !self.out.ends_with("\n\n") {
+            self.out += "\n";
+        }
+    }
+
+    fn doc(mut self, text: &str, context: &[NameSymbol]) -> String {
+        let dedented = textwrap::dedent(text);
+        let item_provider = self.item_provider;
+        // there's nowhere for links to go, but shortcut links to items should still show as links
+        let mut broken_link_callback = |b: BrokenLink<'_>| match b.link_type {
+            LinkType::Shortcut => {
+                let chain = b
+                    .reference
+                    .trim_matches('`')
+                    .split('.')
+                    .map(|x| intern_name(x.trim()));
+                item_provider
+                    .resolve(context, chain)
+                    .map(|_| (CowStr::from(""), CowStr::from("")))
+            }
+            _ => None,
+        };
+        let parser = Parser::new_with_broken_link_callback(
+            &dedented,
+            Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH,
+            Some(&mut broken_link_callback),
+        );
+        for event in parser {
+            match event {
+                Event::Start(Tag::Heading(..)) => self.heading = true,
+                Event::End(Tag::Heading(..)) => {
+                    self.flush();
+                    self.blank_line();
+                    self.heading = false;
+                }
+                Event::End(Tag::Paragraph) => {
+                    self.flush();
+                    self.blank_line();
+                }
+                Event::Start(Tag::BlockQuote) => {
+                    self.flush();
+                    self.prefixes.push("│ ".to_string());
+                }
+                Event::End(Tag::BlockQuote) => {
+                    self.flush();
+                    self.prefixes.pop();
+                }
+                Event::Start(Tag::CodeBlock(_)) => {
+                    self.flush();
+                    self.code_block = true;
+                }
+                Event::End(Tag::CodeBlock(_)) => {
+                    let indent = self.prefixes.concat();
+                    for line in std::mem::take(&mut self.text).trim_end().lines() {
+                        self.out += &format!(
+                            "{}    {}\n",
+                            indent,
+                            paint(self.styled, line, Some(Color::Cyan), &[])
+                        );
+                    }
+                    self.code_block = false;
+                    self.blank_line();
+                }
+                Event::Start(Tag::List(first)) => {
+                    self.flush();
+                    self.lists.push(first);
+                }
+                Event::End(Tag::List(_)) => {
+                    self.flush();
+                    self.lists.pop();
+                    if self.lists.is_empty() {
+                        self.blank_line();
+                    }
+                }
+                Event::Start(Tag::Item) => {
+                    self.flush();
+                    let bullet = match self.lists.last_mut() {
+                        Some(Some(n)) => {
+                            *n += 1;
+                            format!("{}. ", *n - 1)
+                        }
+                        _ => "- ".to_string(),
+                    };
+                    self.prefixes.push(" ".repeat(bullet.chars().count()));
+                    self.bullet = Some(bullet);
+                }
+                Event::End(Tag::Item) => {
+                    self.flush();
+                    self.prefixes.pop();
+                }
+                Event::Start(Tag::TableHead | Tag::TableRow) => self.cell = 0,
+                Event::End(Tag::TableHead | Tag::TableRow) => self.flush(),
+                Event::End(Tag::Table(_)) => self.blank_line(),
+                Event::Start(Tag::TableCell) => {
+                    if self.cell > 0 {
+                        self.text += " | ";
+                    }
+                    self.cell += 1;
+                }
+                Event::Start(Tag::Emphasis) => self.italic += 1,
+                Event::End(Tag::Emphasis) => self.italic -= 1,
+                Event::Start(Tag::Strong) => self.bold += 1,
+                Event::End(Tag::Strong) => self.bold -= 1,
+                Event::Start(Tag::Strikethrough) => self.strikethrough += 1,
+                Event::End(Tag::Strikethrough) => self.strikethrough -= 1,
+                Event::Start(Tag::Link(..)) => self.link += 1,
+                Event::End(Tag::Link(..)) => self.link -= 1,
+                Event::Text(t) | Event::Html(t) => self.push_text(&t),
+                Event::Code(t) => {
+                    self.text += &paint(self.styled, &t, Some(Color::Cyan), &[]);
+                }
+                Event::SoftBreak => self.text += " ",
+                Event::HardBreak => self.flush(),
+                Event::Rule => {
+                    self.flush();
+                    self.out += &format!("{}{}\n", self.prefixes.concat(), "─".repeat(20));
+                    self.blank_line();
+                }
+                _ => {}
+            }
+        }
+        self.flush();
+        self.out.trim_end().to_string()
+    }
+}
+
+fn render_item(
+    item: &ShownItem,
+    item_provider: &ItemProvider,
+    styled: bool,
+    width: usize,
+) -> String {
+    let mut out = paint(styled, &item.signature, None, &[Attribute::Bold]);
+    out += "\n";
+    if let Some(d) = item.deprecated {
+        let notice = if d.reason.is_empty() {
+            format!("Deprecated since {}", d.version)
+        } else {
+            format!("Deprecated since {}: {}", d.version, d.reason)
+        };
+        out += &paint(styled, &notice, Some(Color::Yellow), &[]);
+        out += "\n";
+    }
+    out += "\n";
+    if item.doc_comment.trim().is_empty() {
+        out += &paint(styled, "No documentation.", None, &[Attribute::Dim]);
+    } else {
+        out +=
+            &TerminalWriter::new(item_provider, styled, width).doc(item.doc_comment, item.context);
+    }
+    out += "\n";
+    for (heading, names) in item.children.iter() {
+        if names.is_empty() {
+            continue;
+        }
+        out += "\n";
+        out += &paint(styled, &format!("{}:", heading), None, &[Attribute::Bold]);
+        out += "\n";
+        let options = textwrap::Options::new(width)
+            .initial_indent("  ")
+            .subsequent_indent("  ");
+        out += &textwrap::fill(&names.join(", "), options);
+        out += "\n";
+    }
+    out
+}
+
+/// Prints the signature and documentation of the item at `path`, such as `Actor.Destroy`, or
+/// only its URL if `url_only` is set.
+pub fn show_item<'a>(
+    path: &str,
+    url_only: bool,
+    docs: impl IntoIterator<Item = (Option<&'a str>, &'a Documentation)>,
+    item_provider: &ItemProvider,
+    base: &BaseUrl,
+    canonical_domain: Option<&str>,
+) -> anyhow::Result<()> {
+    let chain = path.split('.').map(|x| intern_name(x.trim()));
+    let Some(linked) = item_provider
+        .resolve(&[], chain)
+        .and_then(|v| v.last().copied())
+    else {
+        anyhow::bail!("couldn't find an item named {:?}", path);
+    };
+
+    if url_only {
+        let href = linked.get_href(base);
+        match (&linked.link_prefix, canonical_domain) {
+            (None, Some(domain)) => println!("{}{}", domain, href),
+            _ => println!("{}", href),
+        }
+        return Ok(());
+    }
+
+    let Some(item) =
+        ItemIndex::new(docs).find(linked.link_prefix.as_deref(), &linked.kind.get_kind_href())
+    else {
+        anyhow::bail!("{:?} isn't part of the generated documentation", path);
+    };
+
+    let styled = std::io::stdout().is_terminal();
+    let width = crossterm::terminal::size()
+        .map(|(w, _)| w as usize)
+        .unwrap_or(80)
+        .clamp(20, 100);
+    print!("{}", render_item(&item, item_provider, styled, width));
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use crate::{structures::BaseUrl, VersionInfo};
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Renders a `sitemap.xml` listing `pages` (given as filenames relative to the documentation root)
+/// under the URL of the version being generated.
+pub fn render_sitemap(canonical_domain: &str, base: &BaseUrl, pages: &[String]) -> String {
+    let mut ret = String::new();
+    ret += "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n";
+    ret += "<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n";
+    for page in pages {
+        ret += &format!(
+            "  <url><loc>{}</loc></url>\n",
+            escape_xml(&format!("{}{}/{}", canonical_domain, base.filled, page))
+        );
+    }
+    ret += "</urlset>\n";
+    ret
+}
+
+/// Renders a `robots.txt` which keeps crawlers out of versions marked `no_index`, and otherwise
+/// points them at the sitemap if there is one.
+pub fn render_robots_txt(
+    canonical_domain: Option<&str>,
+    base: &BaseUrl,
+    version_info: Option<&VersionInfo>,
+) -> String {
+    let mut ret = "User-agent: *\n".to_string();
+    if version_info.map(|v| v.no_index).unwrap_or_default() {
+        ret += &format!("Disallow: {}/\n", base.filled);
+    } else {
+        ret += &format!("Allow: {}/\n", base.filled);
+        if let Some(domain) = canonical_domain {
+            ret += &format!("Sitemap: {}{}/sitemap.xml\n", domain, base.filled);
+        }
+    }
+    ret
+}
@@ -1,6 +1,6 @@
 use zscript_parser::{interner::NameSymbol, Span};
 
-#[derive(Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 pub enum Owner {
     Class(Vec<String>),
     Struct(Vec<String>),
@@ -9,7 +9,7 @@ pub enum Owner {
     Global,
 }
 
-#[derive(Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 pub enum LinkedSectionKind {
     Struct { link: Vec<String> },
     Class { link: Vec<String> },
@@ -30,6 +30,22 @@ pub struct LinkedSection {
     pub kind: LinkedSectionKind,
 }
 
+/// The contents of `items.json`, which lists every item an archive's documentation has a link
+/// target for, so that other archives can link to them without parsing its source.
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct Inventory {
+    pub name: String,
+    pub items: Vec<InventoryItem>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct InventoryItem {
+    /// The names leading to the item, such as `["Actor", "Destroy"]`.
+    pub path: Vec<String>,
+    pub text: String,
+    pub kind: LinkedSectionKind,
+}
+
 #[derive(Debug, Clone)]
 pub enum SourceCodeSection {
     NoLink(String),
@@ -68,6 +84,8 @@ pub struct Function {
     pub doc_comment: String,
     pub signature: SourceCodeWithLinks,
     pub overrides: Option<LinkedSection>,
+    /// The functions of subclasses that override this one, if it's virtual or abstract.
+    pub overridden_by: Vec<LinkedSection>,
     pub deprecated: Option<Deprecated>,
 }
 
@@ -96,6 +114,16 @@ pub struct Constant {
     pub span: Span,
     pub name: String,
     pub def: SourceCodeWithLinks,
+    /// The evaluated value, if it could be evaluated and isn't just what the source says.
+    pub value: Option<String>,
+    /// The elements of a static const array, which are listed separately from `def`.
+    pub elements: Vec<ArrayElement>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ArrayElement {
+    pub expr: String,
+    pub value: Option<String>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -108,11 +136,13 @@ pub struct VariablesAndFunctions {
 pub struct Class {
     pub context: Vec<NameSymbol>,
     pub name: String,
-    #[allow(unused)]
     pub span: Span,
     pub inherits: Option<SourceCodeWithLinks>,
     pub doc_comment: String,
     pub overrides: Vec<Function>,
+    /// The abstract functions, declared by the class or its ancestors, that a subclass has to
+    /// override.
+    pub required_overrides: Vec<LinkedSection>,
     pub public: VariablesAndFunctions,
     pub protected: VariablesAndFunctions,
     pub private: VariablesAndFunctions,
@@ -131,7 +161,6 @@ pub struct Struct {
     pub context: Vec<NameSymbol>,
     pub name: String,
     pub no_context_name: String,
-    #[allow(unused)]
     pub span: Span,
     pub doc_comment: String,
     pub public: VariablesAndFunctions,
@@ -147,10 +176,11 @@ pub struct Struct {
 pub struct Enumerator {
     pub context: Vec<NameSymbol>,
     pub name: String,
-    #[allow(unused)]
     pub span: Span,
     pub doc_comment: String,
     pub decl: SourceCodeWithLinks,
+    /// The enumerator's value, if its expression could be evaluated.
+    pub value: Option<i64>,
 }
 
 #[derive(Debug, Clone)]
@@ -158,21 +188,62 @@ pub struct Enum {
     pub context: Vec<NameSymbol>,
     pub name: String,
     pub no_context_name: String,
-    #[allow(unused)]
     pub span: Span,
     pub doc_comment: String,
     pub enumerators: Vec<Enumerator>,
+    /// Whether the enumerators are bit flags, i.e. all explicitly given powers of two.
+    pub is_flags: bool,
+}
+
+/// An operator of a builtin type.
+#[derive(Debug, Clone)]
+pub struct Operator {
+    pub context: Vec<NameSymbol>,
+    /// The operand types and symbol joined with `.`, with anything an HTML id can't hold escaped,
+    /// used as the operator's anchor.
+    pub id: String,
+    /// The operator written out with its operand types, like `Vector2 * double`.
+    pub name: String,
+    pub filename: String,
+    pub doc_comment: String,
+    pub def: SourceCodeWithLinks,
 }
 
 #[derive(Debug, Clone)]
 pub struct Builtin {
     pub context: Vec<NameSymbol>,
     pub name: String,
+    pub no_context_name: String,
+    /// The type parameters of generic containers, such as `T` for `Array<T>`.
+    pub type_params: Vec<String>,
     pub filename: String,
     pub doc_comment: String,
     pub variables: Vec<MemberVariable>,
     pub functions: Vec<Function>,
     pub constants: Vec<Constant>,
+    pub operators: Vec<Operator>,
+    pub inner_enums: Vec<Enum>,
+    /// Structs nested inside the builtin, which get their own builtin pages.
+    pub inner_structs: Vec<Builtin>,
+}
+
+impl Builtin {
+    /// The type parameters as they follow the builtin's name, such as `<K, V>`, or nothing if it
+    /// isn't generic.
+    pub fn type_params_text(&self) -> String {
+        if self.type_params.is_empty() {
+            String::new()
+        } else {
+            format!("<{}>", self.type_params.join(", "))
+        }
+    }
+
+    /// This builtin followed by every builtin nested inside it, at any depth.
+    pub fn with_nested(&self) -> Vec<&Builtin> {
+        std::iter::once(self)
+            .chain(self.inner_structs.iter().flat_map(|s| s.with_nested()))
+            .collect()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -192,6 +263,14 @@ pub struct Documentation {
 }
 
 pub struct Dependency {
+    pub name: String,
+    pub link: String,
+}
+
+/// The documentation of a dependency, generated only so that it can be included in the search
+/// index.
+pub struct DependencyDocumentation {
+    pub docs: Documentation,
     pub link: String,
 }
 
@@ -213,4 +292,40 @@ impl Dependencies {
 pub struct BaseUrl {
     pub template: String,
     pub filled: String,
+    pub offline: bool,
+}
+
+#[derive(serde::Deserialize, schemars::JsonSchema, Debug, Default, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum BuiltinTheme {
+    #[default]
+    Light,
+    Dark,
+    /// Follows the reader's system preference.
+    Auto,
+}
+
+#[derive(serde::Deserialize, schemars::JsonSchema, Debug, Clone)]
+pub struct NavLink {
+    pub text: String,
+    pub url: String,
+}
+
+/// HTML fragments from `docs/templates/` that are inserted into every page.
+#[derive(Default)]
+pub struct Templates {
+    pub header: Option<String>,
+    pub footer: Option<String>,
+    pub sidebar: Option<String>,
+}
+
+/// Settings that apply to every page of the generated site.
+pub struct SiteInfo {
+    pub canonical_domain: Option<String>,
+    pub site_image: Option<String>,
+    pub builtin_theme: BuiltinTheme,
+    pub stylesheets: Vec<String>,
+    pub scripts: Vec<String>,
+    pub nav_links: Vec<NavLink>,
+    pub templates: Templates,
 }
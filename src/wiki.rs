@@ -0,0 +1,714 @@
+//! Output backends for wikis, which can't host the HTML documentation.
+//!
+//! Each backend implements [`WikiSyntax`], and the layout of the pages themselves is shared.
+
+mod markdown;
+mod mediawiki;
+
+use itertools::Itertools;
+use pulldown_cmark::{BrokenLink, CowStr, Event, LinkType, Options, Parser, Tag};
+use zscript_parser::interner::{intern_name, NameSymbol};
+
+use crate::{consteval::int_hex, item::ItemProvider, structures::*};
+
+pub use markdown::GithubMarkdown;
+pub use mediawiki::MediaWiki;
+
+pub enum LinkTarget {
+    /// A page of the wiki being generated, optionally with an anchor on it.
+    Page {
+        page: String,
+        anchor: Option<String>,
+    },
+    External(String),
+}
+
+/// The markup of one kind of wiki. Text passed to these methods has already been converted, apart
+/// from the arguments of `escape`, `inline_code` and `code_block`.
+pub trait WikiSyntax {
+    fn page_name(&self, kind: &str, name: &str) -> String;
+    fn index_page_name(&self, docs_name: &str) -> String;
+    fn filename(&self, page: &str) -> String;
+
+    fn escape(&self, text: &str) -> String;
+    fn heading(&self, level: usize, text: &str) -> String;
+    /// An invisible anchor placed before an item's heading, so that it can be linked to.
+    fn anchor(&self, id: &str) -> String;
+    fn link(&self, target: &LinkTarget, text: &str) -> String;
+    fn inline_code(&self, code: &str) -> String;
+    fn code_block(&self, lang: &str, code: &str) -> String;
+    fn emphasis(&self, text: &str) -> String;
+    fn strong(&self, text: &str) -> String;
+    fn strikethrough(&self, text: &str) -> String;
+    /// `nesting` has an entry for each list this item is inside of, from the outermost, which is
+    /// `true` for ordered lists. `nested` holds the already-rendered lists inside this item.
+    fn list_item(&self, nesting: &[bool], text: &str, nested: &str) -> String;
+    fn blockquote(&self, text: &str) -> String;
+    fn table(&self, head: &[String], rows: &[Vec<String>]) -> String;
+    fn rule(&self) -> String;
+    fn line_break(&self) -> String;
+}
+
+enum FrameKind {
+    Root,
+    Paragraph,
+    Heading(usize),
+    BlockQuote,
+    CodeBlock(String),
+    List(bool),
+    Item,
+    Emphasis,
+    Strong,
+    Strikethrough,
+    Link(LinkTarget),
+    Table,
+    TableRow,
+    TableCell,
+    Other,
+}
+
+/// A block of markdown being converted, along with what's been converted inside it so far.
+struct Frame {
+    kind: FrameKind,
+    text: String,
+    nested: String,
+    cells: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Frame {
+    fn new(kind: FrameKind) -> Self {
+        Self {
+            kind,
+            text: String::new(),
+            nested: String::new(),
+            cells: vec![],
+            rows: vec![],
+        }
+    }
+}
+
+/// Flattens source code into plain text, broken across lines the same way as in the HTML docs.
+pub fn source_text(source: &SourceCodeWithLinks) -> String {
+    let length: usize = source
+        .sections
+        .iter()
+        .map(|s| match s {
+            SourceCodeSection::NoLink(s) => s.len(),
+            SourceCodeSection::Linked(l) => l.text.len(),
+            SourceCodeSection::NoNewlineSpacing => 1,
+            _ => 0,
+        })
+        .sum();
+    // this matches the line breaking of the HTML renderer
+    if length <= 40 {
+        return source
+            .sections
+            .iter()
+            .map(|s| match s {
+                SourceCodeSection::NoLink(s) => s.as_str(),
+                SourceCodeSection::Linked(l) => l.text.as_str(),
+                SourceCodeSection::NoNewlineSpacing => " ",
+                _ => "",
+            })
+            .collect();
+    }
+    let mut lines = vec![String::new()];
+    for s in source.sections.iter() {
+        match s {
+            SourceCodeSection::NoLink(s) => *lines.last_mut().unwrap() += s,
+            SourceCodeSection::Linked(l) => *lines.last_mut().unwrap() += &l.text,
+            SourceCodeSection::PotentialNewlineOnly => lines.push(String::new()),
+            SourceCodeSection::PotentialNewlineIndent => lines.push("    ".to_string()),
+            SourceCodeSection::NoNewlineSpacing => {}
+        }
+    }
+    lines
+        .into_iter()
+        .filter(|l| !l.trim().is_empty())
+        .join("\n")
+}
+
+struct WikiWriter<'a> {
+    syntax: &'a dyn WikiSyntax,
+    item_provider: &'a ItemProvider,
+    docs_name: &'a str,
+}
+
+impl WikiWriter<'_> {
+    fn owner_page(&self, owner: &Owner) -> String {
+        match owner {
+            Owner::Class(v) => self.syntax.page_name("Class", &v.join(".")),
+            Owner::Struct(v) => self.syntax.page_name("Struct", &v.join(".")),
+            Owner::Enum(v) => self.syntax.page_name("Enum", &v.join(".")),
+            Owner::Builtin(s) => self.syntax.page_name("Builtin", s),
+            Owner::Global => self.syntax.index_page_name(self.docs_name),
+        }
+    }
+
+    fn target(&self, section: &LinkedSection) -> LinkTarget {
+        if let Some(prefix) = &section.link_prefix {
+            // dependencies are only ever documented as HTML
+            return LinkTarget::External(format!("{}{}", prefix, section.kind.get_kind_href()));
+        }
+        let (page, anchor) = match &section.kind {
+            LinkedSectionKind::Struct { link } => {
+                (self.syntax.page_name("Struct", &link.join(".")), None)
+            }
+            LinkedSectionKind::Class { link } => {
+                (self.syntax.page_name("Class", &link.join(".")), None)
+            }
+            LinkedSectionKind::Enum { link } => {
+                (self.syntax.page_name("Enum", &link.join(".")), None)
+            }
+            LinkedSectionKind::Builtin { link } => (self.syntax.page_name("Builtin", link), None),
+            LinkedSectionKind::Function { owner, link } => {
+                (self.owner_page(owner), Some(format!("function.{}", link)))
+            }
+            LinkedSectionKind::Member { owner, link } => {
+                let kind = match owner {
+                    Owner::Global => "global",
+                    _ => "member",
+                };
+                (self.owner_page(owner), Some(format!("{}.{}", kind, link)))
+            }
+            LinkedSectionKind::Enumerator { owner, link } => {
+                (self.owner_page(owner), Some(format!("enumerator.{}", link)))
+            }
+            LinkedSectionKind::Constant { owner, link } => {
+                (self.owner_page(owner), Some(format!("constant.{}", link)))
+            }
+            LinkedSectionKind::Property { owner, link } => {
+                (self.owner_page(owner), Some(format!("property.{}", link)))
+            }
+            LinkedSectionKind::Flag { owner, link } => {
+                (self.owner_page(owner), Some(format!("flag.{}", link)))
+            }
+        };
+        LinkTarget::Page { page, anchor }
+    }
+
+    fn resolve(&self, link: &str, context: &[NameSymbol]) -> Option<LinkTarget> {
+        let chain = link.split('.').map(|x| intern_name(x.trim()));
+        self.item_provider
+            .resolve(context, chain)
+            .map(|v| self.target(v.last().unwrap()))
+    }
+
+    /// Converts a doc comment, with its headings placed below `heading_level`.
+    fn doc(&self, text: &str, context: &[NameSymbol], heading_level: usize) -> String {
+        if text.trim().is_empty() {
+            return String::new();
+        }
+        let dedented = textwrap::dedent(text);
+        // shortcut links to items are resolved when their tag starts
+        let mut broken_link_callback = |b: BrokenLink<'_>| match b.link_type {
+            LinkType::Shortcut => {
+                let reference = b.reference.trim_matches('`').to_string();
+                self.resolve(&reference, context)
+                    .map(|_| (CowStr::from(reference), CowStr::from("")))
+            }
+            _ => None,
+        };
+        let parser = Parser::new_with_broken_link_callback(
+            &dedented,
+            Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH,
+            Some(&mut broken_link_callback),
+        );
+
+        let syntax = self.syntax;
+        let mut stack = vec![Frame::new(FrameKind::Root)];
+        for event in parser {
+            match event {
+                Event::Start(tag) => {
+                    let kind = match tag {
+                        Tag::Paragraph => FrameKind::Paragraph,
+                        Tag::Heading(level, ..) => {
+                            FrameKind::Heading((heading_level + level as usize).min(6))
+                        }
+                        Tag::BlockQuote => FrameKind::BlockQuote,
+                        Tag::CodeBlock(pulldown_cmark::CodeBlockKind::Fenced(lang)) => {
+                            FrameKind::CodeBlock(lang.to_string())
+                        }
+                        Tag::CodeBlock(_) => FrameKind::CodeBlock(String::new()),
+                        Tag::List(start) => FrameKind::List(start.is_some()),
+                        Tag::Item => FrameKind::Item,
+                        Tag::Emphasis => FrameKind::Emphasis,
+                        Tag::Strong => FrameKind::Strong,
+                        Tag::Strikethrough => FrameKind::Strikethrough,
+                        Tag::Link(_, url, _) | Tag::Image(_, url, _) => FrameKind::Link(
+                            self.resolve(&url, context)
+                                .unwrap_or_else(|| LinkTarget::External(url.to_string())),
+                        ),
+                        Tag::Table(_) => FrameKind::Table,
+                        Tag::TableHead | Tag::TableRow => FrameKind::TableRow,
+                        Tag::TableCell => FrameKind::TableCell,
+                        Tag::FootnoteDefinition(_) => FrameKind::Other,
+                    };
+                    stack.push(Frame::new(kind));
+                }
+                Event::End(_) => {
+                    let frame = stack.pop().unwrap();
+                    let nesting = stack
+                        .iter()
+                        .filter_map(|f| match f.kind {
+                            FrameKind::List(ordered) => Some(ordered),
+                            _ => None,
+                        })
+                        .collect_vec();
+                    let parent = stack.last_mut().unwrap();
+                    let text = &frame.text;
+                    match frame.kind {
+                        FrameKind::Paragraph => {
+                            parent.text += text.trim();
+                            parent.text += "\n\n";
+                        }
+                        FrameKind::Heading(level) => parent.text += &syntax.heading(level, text),
+                        FrameKind::BlockQuote => parent.text += &syntax.blockquote(text.trim()),
+                        FrameKind::CodeBlock(lang) => {
+                            parent.text += &syntax.code_block(&lang, text.trim_end())
+                        }
+                        FrameKind::List(_) => {
+                            if let FrameKind::Item = parent.kind {
+                                parent.nested += text;
+                            } else {
+                                parent.text += text;
+                                parent.text += "\n";
+                            }
+                        }
+                        FrameKind::Item => {
+                            let item_text = text.split_whitespace().join(" ");
+                            parent.text += &syntax.list_item(&nesting, &item_text, &frame.nested);
+                        }
+                        FrameKind::Emphasis => parent.text += &syntax.emphasis(text),
+                        FrameKind::Strong => parent.text += &syntax.strong(text),
+                        FrameKind::Strikethrough => parent.text += &syntax.strikethrough(text),
+                        FrameKind::Link(target) => parent.text += &syntax.link(&target, text),
+                        FrameKind::Table => {
+                            let mut rows = frame.rows;
+                            let head = if rows.is_empty() {
+                                vec![]
+                            } else {
+                                rows.remove(0)
+                            };
+                            parent.text += &syntax.table(&head, &rows);
+                        }
+                        FrameKind::TableRow => parent.rows.push(frame.cells),
+                        FrameKind::TableCell => parent.cells.push(text.trim().to_string()),
+                        FrameKind::Root | FrameKind::Other => parent.text += text,
+                    }
+                }
+                Event::Text(t) => {
+                    let top = stack.last_mut().unwrap();
+                    if let FrameKind::CodeBlock(_) = top.kind {
+                        top.text += &t;
+                    } else {
+                        top.text += &syntax.escape(&t);
+                    }
+                }
+                Event::Code(t) => stack.last_mut().unwrap().text += &syntax.inline_code(&t),
+                // like the HTML output, raw HTML is shown as text
+                Event::Html(t) => stack.last_mut().unwrap().text += &syntax.escape(&t),
+                Event::SoftBreak => stack.last_mut().unwrap().text += " ",
+                Event::HardBreak => stack.last_mut().unwrap().text += &syntax.line_break(),
+                Event::Rule => stack.last_mut().unwrap().text += &syntax.rule(),
+                Event::FootnoteReference(_) | Event::TaskListMarker(_) => {}
+            }
+        }
+        stack.pop().unwrap().text
+    }
+
+    fn deprecated(&self, deprecated: &Option<Deprecated>) -> String {
+        match deprecated {
+            Some(d) if d.reason.is_empty() => {
+                format!(
+                    "{}\n\n",
+                    self.syntax
+                        .strong(&format!("Deprecated since {}", d.version))
+                )
+            }
+            Some(d) => format!(
+                "{}\n\n",
+                self.syntax.strong(
+                    &self
+                        .syntax
+                        .escape(&format!("Deprecated since {}: {}", d.version, d.reason))
+                )
+            ),
+            None => String::new(),
+        }
+    }
+
+    /// An item inside a page, with an anchor matching the ids of the HTML output.
+    fn item(
+        &self,
+        id: &str,
+        name: &str,
+        def: &SourceCodeWithLinks,
+        doc_comment: &str,
+        context: &[NameSymbol],
+    ) -> String {
+        format!(
+            "{}{}{}{}",
+            self.syntax.anchor(id),
+            self.syntax.heading(3, &self.syntax.escape(name)),
+            self.syntax.code_block("zscript", &source_text(def)),
+            self.doc(doc_comment, context, 3),
+        )
+    }
+
+    fn section<T>(&self, name: &str, items: &[T], f: impl Fn(&T) -> String) -> String {
+        if items.is_empty() {
+            return String::new();
+        }
+        self.syntax.heading(2, name) + &items.iter().map(f).join("")
+    }
+
+    fn members_and_functions(&self, vis: &str, mf: &VariablesAndFunctions) -> String {
+        self.section(&format!("{} Members", vis), &mf.variables, |v| {
+            self.deprecated(&v.deprecated)
+                + &self.item(
+                    &format!("member.{}", v.name),
+                    &v.name,
+                    &v.def,
+                    &v.doc_comment,
+                    &v.context,
+                )
+        }) + &self.section(&format!("{} Functions", vis), &mf.functions, |f| {
+            self.function(f)
+        })
+    }
+
+    fn function(&self, f: &Function) -> String {
+        let mut ret = self.deprecated(&f.deprecated)
+            + &self.item(
+                &format!("function.{}", f.name),
+                &f.name,
+                &f.signature,
+                &f.doc_comment,
+                &f.context,
+            );
+        if !f.overridden_by.is_empty() {
+            ret += &self.syntax.escape("Overridden by ");
+            ret += &self.linked_sections(&f.overridden_by).join(", ");
+            ret += "\n\n";
+        }
+        ret
+    }
+
+    fn linked_sections(&self, sections: &[LinkedSection]) -> Vec<String> {
+        sections
+            .iter()
+            .map(|l| {
+                self.syntax
+                    .link(&self.target(l), &self.syntax.inline_code(&l.text))
+            })
+            .collect()
+    }
+
+    fn constants(&self, constants: &[Constant]) -> String {
+        self.section("Constants", constants, |c| {
+            let name = match &c.value {
+                Some(v) => format!("{} = {}", c.name, v),
+                None => c.name.clone(),
+            };
+            let mut item = self.item(
+                &format!("constant.{}", c.name),
+                &name,
+                &c.def,
+                &c.doc_comment,
+                &c.context,
+            );
+            if !c.elements.is_empty() {
+                let rows = c
+                    .elements
+                    .iter()
+                    .enumerate()
+                    .map(|(i, e)| {
+                        vec![
+                            i.to_string(),
+                            self.syntax.inline_code(&e.expr),
+                            self.syntax.escape(e.value.as_deref().unwrap_or_default()),
+                        ]
+                    })
+                    .collect_vec();
+                item += &self.syntax.table(
+                    &[
+                        "Index".to_string(),
+                        "Element".to_string(),
+                        "Value".to_string(),
+                    ],
+                    &rows,
+                );
+            }
+            item
+        })
+    }
+
+    fn page_links(&self, name: &str, kind: &str, names: impl Iterator<Item = String>) -> String {
+        let links = names
+            .map(|n| {
+                let target = LinkTarget::Page {
+                    page: self.syntax.page_name(kind, &n),
+                    anchor: None,
+                };
+                self.syntax.list_item(
+                    &[false],
+                    &self.syntax.link(&target, &self.syntax.escape(&n)),
+                    "",
+                )
+            })
+            .join("");
+        if links.is_empty() {
+            return String::new();
+        }
+        self.syntax.heading(2, name) + &links + "\n"
+    }
+
+    fn class(&self, c: &Class) -> String {
+        let mut page = self
+            .syntax
+            .heading(1, &self.syntax.escape(&format!("Class {}", c.name)));
+        if let Some(inherits) = &c.inherits {
+            page += &self.syntax.escape("Inherits from ");
+            for s in inherits.sections.iter() {
+                match s {
+                    SourceCodeSection::Linked(l) => {
+                        page += &self
+                            .syntax
+                            .link(&self.target(l), &self.syntax.inline_code(&l.text))
+                    }
+                    SourceCodeSection::NoLink(t) if !t.trim().is_empty() => {
+                        page += &self.syntax.inline_code(t.trim())
+                    }
+                    _ => {}
+                }
+            }
+            page += "\n\n";
+        }
+        page += &self.deprecated(&c.deprecated);
+        page += &self.doc(&c.doc_comment, &c.context, 1);
+        page += &self.constants(&c.constants);
+        page += &self.section("Properties", &c.properties, |p| {
+            self.item(
+                &format!("property.{}", p.name),
+                &p.name,
+                &p.def,
+                &p.doc_comment,
+                &p.context,
+            )
+        });
+        page += &self.section("Flags", &c.flags, |f| {
+            self.item(
+                &format!("flag.{}", f.name),
+                &f.name,
+                &f.def,
+                &f.doc_comment,
+                &f.context,
+            )
+        });
+        page += &self.members_and_functions("Public", &c.public);
+        page += &self.members_and_functions("Protected", &c.protected);
+        if !c.required_overrides.is_empty() {
+            page += &self.syntax.heading(2, "Required Overrides");
+            for l in self.linked_sections(&c.required_overrides) {
+                page += &self.syntax.list_item(&[false], &l, "");
+            }
+            page += "\n";
+        }
+        page += &self.page_links(
+            "Inner Structs",
+            "Struct",
+            c.inner_structs.iter().map(|s| s.name.clone()),
+        );
+        page += &self.page_links(
+            "Inner Enums",
+            "Enum",
+            c.inner_enums.iter().map(|e| e.name.clone()),
+        );
+        page
+    }
+
+    fn strukt(&self, s: &Struct) -> String {
+        let mut page = self
+            .syntax
+            .heading(1, &self.syntax.escape(&format!("Struct {}", s.name)));
+        page += &self.deprecated(&s.deprecated);
+        page += &self.doc(&s.doc_comment, &s.context, 1);
+        page += &self.constants(&s.constants);
+        page += &self.members_and_functions("Public", &s.public);
+        page += &self.members_and_functions("Protected", &s.protected);
+        page += &self.page_links(
+            "Inner Enums",
+            "Enum",
+            s.inner_enums.iter().map(|e| e.name.clone()),
+        );
+        page
+    }
+
+    fn enm(&self, e: &Enum) -> String {
+        let mut page = self
+            .syntax
+            .heading(1, &self.syntax.escape(&format!("Enum {}", e.name)));
+        page += &self.doc(&e.doc_comment, &e.context, 1);
+        if e.is_flags {
+            let rows = e
+                .enumerators
+                .iter()
+                .filter_map(|en| Some((en, en.value.filter(|v| *v != 0)?)))
+                .map(|(en, v)| {
+                    vec![
+                        self.syntax.inline_code(&en.name),
+                        v.trailing_zeros().to_string(),
+                        self.syntax.inline_code(&int_hex(v)),
+                    ]
+                })
+                .collect_vec();
+            page += &self.syntax.heading(2, "Flags");
+            page += &self.syntax.table(
+                &["Name".to_string(), "Bit".to_string(), "Value".to_string()],
+                &rows,
+            );
+        }
+        page += &self.section("Enumerators", &e.enumerators, |en| {
+            let name = match en.value {
+                Some(v) => format!("{} = {} ({})", en.name, v, int_hex(v)),
+                None => en.name.clone(),
+            };
+            self.item(
+                &format!("enumerator.{}", en.name),
+                &name,
+                &en.decl,
+                &en.doc_comment,
+                &en.context,
+            )
+        });
+        page
+    }
+
+    fn builtin(&self, b: &Builtin) -> String {
+        let mut page = self.syntax.heading(
+            1,
+            &self
+                .syntax
+                .escape(&format!("Builtin {}{}", b.name, b.type_params_text())),
+        );
+        page += &self.doc(&b.doc_comment, &b.context, 1);
+        page += &self.constants(&b.constants);
+        page += &self.section("Members", &b.variables, |v| {
+            self.item(
+                &format!("member.{}", v.name),
+                &v.name,
+                &v.def,
+                &v.doc_comment,
+                &v.context,
+            )
+        });
+        page += &self.section("Functions", &b.functions, |f| self.function(f));
+        page += &self.section("Operators", &b.operators, |o| {
+            self.item(
+                &format!("operator.{}", o.id),
+                &o.name,
+                &o.def,
+                &o.doc_comment,
+                &o.context,
+            )
+        });
+        page += &self.page_links(
+            "Inner Structs",
+            "Builtin",
+            b.inner_structs.iter().map(|s| s.name.clone()),
+        );
+        page += &self.page_links(
+            "Inner Enums",
+            "Enum",
+            b.inner_enums.iter().map(|e| e.name.clone()),
+        );
+        page
+    }
+
+    fn index(&self, docs: &Documentation) -> String {
+        let mut page = self.syntax.heading(
+            1,
+            &self.syntax.escape(&format!("{} Documentation", docs.name)),
+        );
+        page += &self.doc(&docs.summary_doc, &[], 1);
+        if let Some(g) = &docs.globals {
+            page += &self.section("Global Variables", &g.variables, |v| {
+                self.deprecated(&v.deprecated)
+                    + &self.item(
+                        &format!("global.{}", v.name),
+                        &v.name,
+                        &v.def,
+                        &v.doc_comment,
+                        &v.context,
+                    )
+            });
+        }
+        page += &self.constants(&docs.constants);
+        page += &self.page_links(
+            "Builtin Types",
+            "Builtin",
+            docs.builtins.iter().map(|b| b.name.clone()),
+        );
+        page += &self.page_links(
+            "Classes",
+            "Class",
+            docs.classes.iter().map(|c| c.name.clone()),
+        );
+        page += &self.page_links(
+            "Structs",
+            "Struct",
+            docs.structs.iter().map(|s| s.name.clone()),
+        );
+        page += &self.page_links("Enums", "Enum", docs.enums.iter().map(|e| e.name.clone()));
+        page
+    }
+}
+
+/// Renders every page of the documentation, returning pairs of filenames and their contents.
+pub fn render_pages(
+    docs: &Documentation,
+    item_provider: &ItemProvider,
+    syntax: &dyn WikiSyntax,
+) -> Vec<(String, String)> {
+    let writer = WikiWriter {
+        syntax,
+        item_provider,
+        docs_name: &docs.name,
+    };
+    let page = |kind: &str, name: &str, contents: String| {
+        (syntax.filename(&syntax.page_name(kind, name)), contents)
+    };
+    let mut pages = vec![(
+        syntax.filename(&syntax.index_page_name(&docs.name)),
+        writer.index(docs),
+    )];
+    let enums = |enums: &[Enum]| {
+        enums
+            .iter()
+            .map(|e| page("Enum", &e.name, writer.enm(e)))
+            .collect_vec()
+    };
+    let structs = |structs: &[Struct]| {
+        structs
+            .iter()
+            .flat_map(|s| {
+                std::iter::once(page("Struct", &s.name, writer.strukt(s)))
+                    .chain(enums(&s.inner_enums))
+            })
+            .collect_vec()
+    };
+    for c in docs.classes.iter() {
+        pages.push(page("Class", &c.name, writer.class(c)));
+        pages.extend(structs(&c.inner_structs));
+        pages.extend(enums(&c.inner_enums));
+    }
+    pages.extend(structs(&docs.structs));
+    pages.extend(enums(&docs.enums));
+    for b in docs.builtins.iter().flat_map(|b| b.with_nested()) {
+        pages.push(page("Builtin", &b.name, writer.builtin(b)));
+        pages.extend(enums(&b.inner_enums));
+    }
+    pages
+}
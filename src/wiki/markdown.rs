@@ -0,0 +1,116 @@
+use itertools::Itertools;
+
+use super::{LinkTarget, WikiSyntax};
+
+/// GitHub-flavored markdown, laid out for a GitHub wiki.
+pub struct GithubMarkdown;
+
+impl WikiSyntax for GithubMarkdown {
+    fn page_name(&self, kind: &str, name: &str) -> String {
+        format!("{}-{}", kind, name)
+    }
+
+    fn index_page_name(&self, _docs_name: &str) -> String {
+        // GitHub wikis use this page as their front page
+        "Home".to_string()
+    }
+
+    fn filename(&self, page: &str) -> String {
+        format!("{}.md", page)
+    }
+
+    fn escape(&self, text: &str) -> String {
+        let mut escaped = String::with_capacity(text.len());
+        for c in text.chars() {
+            if matches!(
+                c,
+                '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '|' | '#'
+            ) {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+        }
+        escaped
+    }
+
+    fn heading(&self, level: usize, text: &str) -> String {
+        format!("{} {}\n\n", "#".repeat(level.min(6)), text.trim())
+    }
+
+    fn anchor(&self, id: &str) -> String {
+        // the blank line ends the HTML block, so that the heading after it is still parsed
+        format!("<a id=\"{}\"></a>\n\n", id)
+    }
+
+    fn link(&self, target: &LinkTarget, text: &str) -> String {
+        let url = match target {
+            LinkTarget::Page { page, anchor: None } => page.clone(),
+            LinkTarget::Page {
+                page,
+                anchor: Some(anchor),
+            } => format!("{}#{}", page, anchor),
+            LinkTarget::External(url) => url.clone(),
+        };
+        if url.contains([' ', '(', ')']) {
+            format!("[{}](<{}>)", text, url)
+        } else {
+            format!("[{}]({})", text, url)
+        }
+    }
+
+    fn inline_code(&self, code: &str) -> String {
+        if code.contains('`') {
+            format!("`` {} ``", code)
+        } else {
+            format!("`{}`", code)
+        }
+    }
+
+    fn code_block(&self, lang: &str, code: &str) -> String {
+        format!("```{}\n{}\n```\n\n", lang, code)
+    }
+
+    fn emphasis(&self, text: &str) -> String {
+        format!("*{}*", text)
+    }
+
+    fn strong(&self, text: &str) -> String {
+        format!("**{}**", text)
+    }
+
+    fn strikethrough(&self, text: &str) -> String {
+        format!("~~{}~~", text)
+    }
+
+    fn list_item(&self, nesting: &[bool], text: &str, nested: &str) -> String {
+        let indent = "    ".repeat(nesting.len().saturating_sub(1));
+        let marker = if nesting.last().copied().unwrap_or_default() {
+            "1."
+        } else {
+            "-"
+        };
+        format!("{}{} {}\n{}", indent, marker, text, nested)
+    }
+
+    fn blockquote(&self, text: &str) -> String {
+        text.lines().map(|l| format!("> {}", l)).join("\n") + "\n\n"
+    }
+
+    fn table(&self, head: &[String], rows: &[Vec<String>]) -> String {
+        let row = |cells: &[String]| format!("| {} |\n", cells.join(" | "));
+        let mut table = row(head);
+        table += &row(&head.iter().map(|_| "---".to_string()).collect_vec());
+        for r in rows {
+            table += &row(r);
+        }
+        table + "\n"
+    }
+
+    fn rule(&self) -> String {
+        "---\n\n".to_string()
+    }
+
+    fn line_break(&self) -> String {
+        "\\\n".to_string()
+    }
+}
@@ -0,0 +1,111 @@
+use itertools::Itertools;
+
+use super::{LinkTarget, WikiSyntax};
+
+/// MediaWiki markup, as used by the ZDoom wiki.
+pub struct MediaWiki;
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::new();
+    pulldown_cmark::escape::escape_html(&mut escaped, text).unwrap();
+    escaped
+}
+
+impl WikiSyntax for MediaWiki {
+    fn page_name(&self, kind: &str, name: &str) -> String {
+        format!("{} {}", kind, name)
+    }
+
+    fn index_page_name(&self, docs_name: &str) -> String {
+        format!("{} Documentation", docs_name)
+    }
+
+    fn filename(&self, page: &str) -> String {
+        // MediaWiki treats underscores in titles as spaces
+        format!("{}.wiki", page.replace(' ', "_"))
+    }
+
+    fn escape(&self, text: &str) -> String {
+        let escaped = escape_html(text);
+        let needs_nowiki = ["[", "]", "{{", "}}", "''", "|", "~~~", "__"]
+            .iter()
+            .any(|s| escaped.contains(s))
+            || escaped.starts_with(['*', '#', ':', ';', '=', ' ']);
+        if needs_nowiki {
+            format!("<nowiki>{}</nowiki>", escaped)
+        } else {
+            escaped
+        }
+    }
+
+    fn heading(&self, level: usize, text: &str) -> String {
+        let equals = "=".repeat(level.min(6));
+        format!("{} {} {}\n\n", equals, text.trim(), equals)
+    }
+
+    fn anchor(&self, id: &str) -> String {
+        format!("<span id=\"{}\"></span>\n", id)
+    }
+
+    fn link(&self, target: &LinkTarget, text: &str) -> String {
+        match target {
+            LinkTarget::Page { page, anchor: None } => format!("[[{}|{}]]", page, text),
+            LinkTarget::Page {
+                page,
+                anchor: Some(anchor),
+            } => format!("[[{}#{}|{}]]", page, anchor, text),
+            LinkTarget::External(url) => format!("[{} {}]", url, text),
+        }
+    }
+
+    fn inline_code(&self, code: &str) -> String {
+        format!("<code><nowiki>{}</nowiki></code>", escape_html(code))
+    }
+
+    fn code_block(&self, _lang: &str, code: &str) -> String {
+        format!("<pre>{}</pre>\n\n", escape_html(code))
+    }
+
+    fn emphasis(&self, text: &str) -> String {
+        format!("''{}''", text)
+    }
+
+    fn strong(&self, text: &str) -> String {
+        format!("'''{}'''", text)
+    }
+
+    fn strikethrough(&self, text: &str) -> String {
+        format!("<s>{}</s>", text)
+    }
+
+    fn list_item(&self, nesting: &[bool], text: &str, nested: &str) -> String {
+        let markers: String = nesting
+            .iter()
+            .map(|ordered| if *ordered { '#' } else { '*' })
+            .collect();
+        format!("{} {}\n{}", markers, text, nested)
+    }
+
+    fn blockquote(&self, text: &str) -> String {
+        format!("<blockquote>{}</blockquote>\n\n", text)
+    }
+
+    fn table(&self, head: &[String], rows: &[Vec<String>]) -> String {
+        let mut table = "{| class=\"wikitable\"\n".to_string();
+        if !head.is_empty() {
+            table += &format!("! {}\n", head.join(" !! "));
+        }
+        for r in rows {
+            table += &format!("|-\n| {}\n", r.iter().join(" || "));
+        }
+        table + "|}\n\n"
+    }
+
+    fn rule(&self) -> String {
+        "----\n\n".to_string()
+    }
+
+    fn line_break(&self) -> String {
+        "<br />".to_string()
+    }
+}
@@ -11,25 +11,145 @@ type SearchResultKind =
     | "Member"
     | "Constant"
     | "Enumerator"
-    | "Global";
+    | "Property"
+    | "Flag"
+    | "Global"
+    | "Page";
+
+// see `SearchIndex` in `search.rs` for the format of these files
+type SearchIndexEntry = [string, string, string, SearchResultKind, number];
+
+interface SearchIndex {
+    shard_size: number;
+    archives: Array<string>;
+    results: Array<SearchIndexEntry>;
+}
 
 type SearchResult = {
     name_prelude: string;
     name: string;
     link: string;
-    desc: string;
     kind: SearchResultKind;
+    index: number;
+    // the name of the dependency this comes from, or null for the documented archive
+    archive: string | null;
 };
 
 interface SearchResults {
+    shard_size: number;
     results: Array<SearchResult>;
 }
 
+type FullTextDocument = {
+    name_prelude: string;
+    name: string;
+    link: string;
+    snippet: string;
+    kind: SearchResultKind;
+};
+
+interface FullTextIndex {
+    documents: Array<FullTextDocument>;
+    // maps a term to pairs of [index into `documents`, occurrence count]
+    terms: Record<string, Array<[number, number]>>;
+}
+
+declare global {
+    interface Window {
+        zscdoc_search_results?: SearchIndex;
+        zscdoc_search_shards?: Record<number, Array<string>>;
+        zscdoc_full_text_index?: FullTextIndex;
+    }
+}
+
+const KIND_WEIGHTS: Record<SearchResultKind, number> = {
+    Class: 0.1,
+    Struct: 0.1,
+    Enum: 0.1,
+    Builtin: 0.1,
+    Function: 0,
+    Member: 0,
+    Constant: 0,
+    Enumerator: 0,
+    Property: 0,
+    Flag: 0,
+    Global: 0,
+    Page: 0,
+};
+
+const KIND_CLASSES: Record<SearchResultKind, string> = {
+    Class: "class",
+    Struct: "struct",
+    Enum: "enum",
+    Builtin: "builtin",
+    Function: "function",
+    Member: "member",
+    Constant: "constant",
+    Enumerator: "constant",
+    Property: "default",
+    Flag: "default",
+    Global: "member",
+    Page: "default",
+};
+
+function is_offline(): boolean {
+    // offline builds can't `fetch` when opened from disk, so they ship the
+    // index as `search.js` which is loaded by a script tag instead
+    return window.zscdoc_search_results !== undefined;
+}
+
+function load_script(src: string): Promise<void> {
+    return new Promise((resolve, reject) => {
+        const script = document.createElement("script");
+        script.src = src;
+        script.onload = () => resolve();
+        script.onerror = reject;
+        document.head.appendChild(script);
+    });
+}
+
 async function get_search_results(): Promise<SearchResults> {
-    const res = await fetch("search.json");
-    const res_json = await res.json();
-    return res_json as SearchResults;
+    let index: SearchIndex;
+    if (window.zscdoc_search_results !== undefined) {
+        index = window.zscdoc_search_results;
+    } else {
+        const res = await fetch("search.json");
+        index = (await res.json()) as SearchIndex;
+    }
+    return {
+        shard_size: index.shard_size,
+        results: index.results.map(
+            ([name_prelude, name, link, kind, archive], i) => ({
+                name_prelude,
+                name,
+                link,
+                kind,
+                index: i,
+                archive: archive === 0 ? null : index.archives[archive],
+            }),
+        ),
+    };
 }
+
+const description_shards = new Map<number, Promise<Array<string>>>();
+function get_description_shard(shard: number): Promise<Array<string>> {
+    let promise = description_shards.get(shard);
+    if (promise === undefined) {
+        if (is_offline()) {
+            promise = load_script(`search/desc.${shard}.js`).then(
+                // eslint-disable-next-line @typescript-eslint/no-non-null-assertion
+                () => window.zscdoc_search_shards![shard],
+            );
+        } else {
+            promise = fetch(`search/desc.${shard}.json`).then(
+                (res) => res.json() as Promise<Array<string>>,
+            );
+        }
+        description_shards.set(shard, promise);
+    }
+    return promise;
+}
+
 function search(
     text: string,
     search_results: SearchResults,
@@ -42,81 +162,156 @@ function search(
             // fuzzysort's type declarations appear to be wrong here
             const a = e as unknown as Fuzzysort.KeysResult<SearchResult>;
             if (a[0] == null) return -10001;
-            // this very slightly weights the searches towards types rather than things inside types
-            return (
-                a[0].score +
-                {
-                    Class: 0.1,
-                    Struct: 0.1,
-                    Enum: 0.1,
-                    Builtin: 0.1,
-                    Function: 0,
-                    Member: 0,
-                    Constant: 0,
-                    Enumerator: 0,
-                    Global: 0,
-                }[a.obj.kind]
-            );
+            // this very slightly weights the searches towards types rather than things inside types,
+            // and towards the documented archive rather than its dependencies
+            const dependency_weight = a.obj.archive === null ? 0 : -0.2;
+            return a[0].score + KIND_WEIGHTS[a.obj.kind] + dependency_weight;
         },
     });
 }
 
+let full_text_index: Promise<FullTextIndex> | null = null;
+function get_full_text_index(): Promise<FullTextIndex> {
+    if (full_text_index !== null) {
+        return full_text_index;
+    }
+    if (is_offline()) {
+        full_text_index = load_script("fulltext.js").then(
+            // eslint-disable-next-line @typescript-eslint/no-non-null-assertion
+            () => window.zscdoc_full_text_index!,
+        );
+    } else {
+        full_text_index = fetch("fulltext.json").then(
+            (res) => res.json() as Promise<FullTextIndex>,
+        );
+    }
+    return full_text_index;
+}
+
+// these match `STOP_WORDS` in src/search.rs, which aren't in the index
+const STOP_WORDS = new Set([
+    "a",
+    "an",
+    "and",
+    "are",
+    "as",
+    "at",
+    "be",
+    "by",
+    "for",
+    "from",
+    "if",
+    "in",
+    "is",
+    "it",
+    "of",
+    "on",
+    "or",
+    "so",
+    "that",
+    "the",
+    "this",
+    "to",
+    "was",
+    "with",
+]);
+
+let full_text_terms: Array<string> | null = null;
+function full_text_search(
+    text: string,
+    index: FullTextIndex,
+): Array<FullTextDocument> {
+    const words = text
+        .toLowerCase()
+        .split(/[^\p{L}\p{N}_]+/u)
+        // the same words the index is made of
+        .filter((w) => w.length > 1 && !STOP_WORDS.has(w));
+    if (words.length === 0) {
+        return [];
+    }
+    if (full_text_terms === null) {
+        full_text_terms = Object.keys(index.terms);
+    }
+    const doc_count = index.documents.length;
+    let scores: Map<number, number> | null = null;
+    for (const [i, word] of words.entries()) {
+        // every word of the query has to appear in a document, but the last
+        // one is matched as a prefix since it's likely still being typed
+        const is_last = i === words.length - 1;
+        const terms = full_text_terms.filter((t) =>
+            is_last ? t.startsWith(word) : t === word,
+        );
+        const word_scores = new Map<number, number>();
+        for (const term of terms) {
+            const postings = index.terms[term];
+            const idf = Math.log(1 + doc_count / postings.length);
+            const exact = term === word ? 2 : 1;
+            for (const [doc, count] of postings) {
+                word_scores.set(
+                    doc,
+                    (word_scores.get(doc) ?? 0) + count * idf * exact,
+                );
+            }
+        }
+        if (scores === null) {
+            scores = word_scores;
+        } else {
+            const next_scores = new Map<number, number>();
+            for (const [doc, score] of scores) {
+                const word_score = word_scores.get(doc);
+                if (word_score !== undefined) {
+                    next_scores.set(doc, score + word_score);
+                }
+            }
+            scores = next_scores;
+        }
+    }
+    return [...(scores ?? new Map<number, number>()).entries()]
+        .sort((a, b) => b[1] - a[1])
+        .slice(0, 10)
+        .map(([doc]) => index.documents[doc]);
+}
+
 function add_zws(text: string): string {
     for (const c of [".", "_"]) {
         text = text.replace(c, `\u{200B}${c}`);
     }
     return text;
 }
-function render_search_results(results: Fuzzysort.KeysResults<SearchResult>) {
+function render_search_results(
+    shard_size: number,
+    results: Fuzzysort.KeysResults<SearchResult>,
+    full_text_results: Array<FullTextDocument>,
+) {
     // eslint-disable-next-line @typescript-eslint/no-non-null-assertion
     const search_node = document.getElementById("search")!;
     const search_results_node = document.getElementById("search_results");
     search_results_node?.remove();
-    function desc_node(desc: string): HTMLElement {
+    function desc_node(result: SearchResult): HTMLElement {
         const base = <div class="search_text search_desc"></div>;
-        base.innerHTML = desc;
+        const shard = Math.floor(result.index / shard_size);
+        get_description_shard(shard).then((descs) => {
+            base.innerHTML = descs[result.index % shard_size];
+        });
         return base;
     }
-    if (results.length != 0) {
-        const results_slice = results.slice(0, 10);
+    function result_class(last: boolean): string {
+        return last ? "search_result" : "search_result search_result_border";
+    }
+    const results_slice = results.slice(0, 10);
+    const total = results_slice.length + full_text_results.length;
+    if (total != 0) {
         const new_search_results = (
             <div id="search_results">
                 {results_slice.map((r, i) => (
                     <a href={r.obj.link} class="search_result_link">
-                        <div
-                            class={`
-                                    search_result
-                                    ${
-                                        i !== results_slice.length - 1
-                                            ? " search_result_border"
-                                            : ""
-                                    }
-                                `
-                                .trim()
-                                .replace(/\s+/g, " ")}
-                        >
+                        <div class={result_class(i === total - 1)}>
                             <div class="search_text search_kind">
                                 {r.obj.kind.toLowerCase()}
                             </div>
                             <div class="search_text search_name">
                                 {add_zws(r.obj.name_prelude)}
-                                <span
-                                    class={`
-                                        ${
-                                            {
-                                                Class: "class",
-                                                Struct: "struct",
-                                                Enum: "enum",
-                                                Builtin: "builtin",
-                                                Function: "function",
-                                                Member: "member",
-                                                Constant: "constant",
-                                                Enumerator: "constant",
-                                                Global: "member",
-                                            }[r.obj.kind]
-                                        }
-                                    `}
-                                >
+                                <span class={KIND_CLASSES[r.obj.kind]}>
                                     {fuzzysort
                                         .highlight(r[0], (m) => (
                                             <span class="highlight_emphasis">
@@ -131,8 +326,44 @@ function render_search_results(results: Fuzzysort.KeysResults<SearchResult>) {
                                             }
                                         })}
                                 </span>
+                                {r.obj.archive !== null ? (
+                                    <span class="search_archive">
+                                        {r.obj.archive}
+                                    </span>
+                                ) : (
+                                    []
+                                )}
+                            </div>
+                            {desc_node(r.obj)}
+                        </div>
+                    </a>
+                ))}
+                {full_text_results.length != 0 ? (
+                    <div class="search_section_header search_result_border">
+                        Mentioned in the documentation
+                    </div>
+                ) : (
+                    []
+                )}
+                {full_text_results.map((r, i) => (
+                    <a href={r.link} class="search_result_link">
+                        <div
+                            class={result_class(
+                                results_slice.length + i === total - 1,
+                            )}
+                        >
+                            <div class="search_text search_kind">
+                                {r.kind.toLowerCase()}
+                            </div>
+                            <div class="search_text search_name">
+                                {add_zws(r.name_prelude)}
+                                <span class={KIND_CLASSES[r.kind]}>
+                                    {add_zws(r.name)}
+                                </span>
+                            </div>
+                            <div class="search_text search_desc">
+                                {r.snippet}
                             </div>
-                            {desc_node(r.obj.desc)}
                         </div>
                     </a>
                 ))}
@@ -156,16 +387,30 @@ export default async () => {
     let last_promise: Fuzzysort.CancelablePromise<
         Fuzzysort.KeysResults<SearchResult>
     > | null = null;
-    search_input.addEventListener("input", () => {
+    let search_count = 0;
+    const run_search = () => {
         last_promise?.cancel();
-        last_promise = search(search_input.value, search_results);
-        last_promise.then((s) => render_search_results(s));
-    });
-    search_input.addEventListener("focus", () => {
-        last_promise?.cancel();
-        last_promise = search(search_input.value, search_results);
-        last_promise.then((s) => render_search_results(s));
-    });
+        const text = search_input.value;
+        const this_search = ++search_count;
+        last_promise = search(text, search_results);
+        last_promise.then(async (s) => {
+            render_search_results(search_results.shard_size, s, []);
+            if (text.trim().length < 3) {
+                return;
+            }
+            // the full text index is much larger, so only fetch it once it's needed
+            const index = await get_full_text_index();
+            if (this_search === search_count) {
+                render_search_results(
+                    search_results.shard_size,
+                    s,
+                    full_text_search(text, index),
+                );
+            }
+        });
+    };
+    search_input.addEventListener("input", run_search);
+    search_input.addEventListener("focus", run_search);
     // eslint-disable-next-line @typescript-eslint/no-non-null-assertion
     const search_node = document.getElementById("search")!;
 
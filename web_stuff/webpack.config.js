@@ -7,6 +7,7 @@ const MiniCssExtractPlugin = require("mini-css-extract-plugin");
 module.exports = {
     entry: {
         main: "./src/main.tsx",
+        dark: "./src/dark.scss",
     },
     mode: "production",
     output: {